@@ -0,0 +1,144 @@
+export type OptionType = "boolean" | "string" | "number" | "list";
+
+export interface OptionSpec {
+  type: OptionType;
+  alias?: string;
+  placeholder?: string;
+  description: string;
+}
+
+export type Options = Record<string, OptionSpec>;
+
+type Value = string | number | boolean | string[];
+
+export class UsageError extends Error {}
+
+export class Args {
+  constructor(
+    readonly positionals: string[],
+    private readonly values: Map<string, Value>
+  ) {}
+
+  has(name: string): boolean {
+    return this.values.has(name);
+  }
+
+  boolean(name: string): boolean {
+    return this.values.get(name) === true;
+  }
+
+  string(name: string): string | undefined {
+    const value = this.values.get(name);
+    return typeof value === "string" ? value : undefined;
+  }
+
+  number(name: string): number | undefined {
+    const value = this.values.get(name);
+    return typeof value === "number" ? value : undefined;
+  }
+
+  list(name: string): string[] {
+    const value = this.values.get(name);
+    return Array.isArray(value) ? value : [];
+  }
+}
+
+export function parseArgs(argv: string[], options: Options): Args {
+  const positionals: string[] = [];
+  const values = new Map<string, Value>();
+  const aliases = new Map(
+    Object.entries(options)
+      .filter(([, spec]) => spec.alias !== undefined)
+      .map(([name, spec]) => [spec.alias as string, name])
+  );
+
+  for (let i = 0; i < argv.length; i++) {
+    const arg = argv[i];
+
+    if (arg === "--") {
+      positionals.push(...argv.slice(i + 1));
+      break;
+    }
+
+    if (!arg.startsWith("-") || arg === "-") {
+      positionals.push(arg);
+      continue;
+    }
+
+    let [key, inline] = splitOnce(arg.replace(/^--?/, ""), "=");
+    if (!arg.startsWith("--")) {
+      key = aliases.get(key) ?? key;
+    }
+
+    let negated = false;
+    if (!(key in options) && key.startsWith("no-") && key.slice(3) in options) {
+      key = key.slice(3);
+      negated = true;
+    }
+
+    const spec = options[key];
+    if (spec === undefined) {
+      throw new UsageError(`Unknown option: ${arg}`);
+    }
+
+    if (spec.type === "boolean") {
+      if (inline !== undefined) {
+        throw new UsageError(`Option --${key} does not take a value`);
+      }
+      values.set(key, !negated);
+      continue;
+    }
+
+    if (negated) {
+      throw new UsageError(`Unknown option: ${arg}`);
+    }
+
+    const raw = inline ?? argv[++i];
+    if (raw === undefined) {
+      throw new UsageError(`Option --${key} requires a value`);
+    }
+
+    switch (spec.type) {
+      case "string":
+        values.set(key, raw);
+        break;
+      case "number": {
+        const num = Number(raw);
+        if (raw.trim() === "" || Number.isNaN(num)) {
+          throw new UsageError(`Option --${key} expects a number, got '${raw}'`);
+        }
+        values.set(key, num);
+        break;
+      }
+      case "list": {
+        const prev = values.get(key);
+        const items = raw
+          .split(",")
+          .map((item) => item.trim())
+          .filter((item) => item.length > 0);
+        values.set(key, [...(Array.isArray(prev) ? prev : []), ...items]);
+        break;
+      }
+    }
+  }
+
+  return new Args(positionals, values);
+}
+
+export function formatOptions(options: Options): string {
+  const lines = Object.entries(options).map(([name, spec]) => {
+    const alias = spec.alias ? `-${spec.alias}, ` : "    ";
+    const value =
+      spec.type === "boolean" ? "" : ` <${spec.placeholder ?? spec.type}>`;
+    return [`${alias}--${name}${value}`, spec.description];
+  });
+  const width = Math.max(0, ...lines.map(([flag]) => flag.length));
+  return lines
+    .map(([flag, description]) => `  ${flag.padEnd(width)}  ${description}`)
+    .join("\n");
+}
+
+function splitOnce(str: string, sep: string): [string, string | undefined] {
+  const idx = str.indexOf(sep);
+  return idx === -1 ? [str, undefined] : [str.slice(0, idx), str.slice(idx + 1)];
+}
@@ -0,0 +1,65 @@
+import readline from "readline";
+import { block, follow, unblock, unfollow } from "./github";
+import { JournalAction, record } from "./journal";
+import { ListName, loadList } from "./lists";
+
+const operations: Record<
+  JournalAction,
+  { verb: string; run: (username: string, auth: string) => Promise<void> }
+> = {
+  follow: { verb: "followed", run: follow },
+  unfollow: { verb: "unfollowed", run: unfollow },
+  block: { verb: "blocked", run: block },
+  unblock: { verb: "unblocked", run: unblock },
+};
+
+// Which list protects an account from an action.
+const guards: Partial<Record<JournalAction, ListName>> = {
+  unfollow: "allowlist",
+  block: "allowlist",
+  follow: "denylist",
+};
+
+export async function applyToLogins(
+  logins: string[],
+  action: JournalAction,
+  token: string
+): Promise<number> {
+  const { verb, run } = operations[action];
+  const guard = guards[action];
+  const isListed = guard ? await loadList(guard, token) : () => false;
+  let failed = 0;
+  for (const login of logins) {
+    if (isListed(login)) {
+      console.log(`skipped ${login}: listed in ${guard}`);
+      continue;
+    }
+    try {
+      await run(login, token);
+      record({ login, action, result: "ok" });
+      console.log(`${verb} ${login}`);
+    } catch (err) {
+      failed++;
+      record({ login, action, result: "failed", error: err.message });
+      console.error(`failed: ${login}: ${err.message}`);
+    }
+  }
+  return failed > 0 ? 1 : 0;
+}
+
+export async function confirm(question: string): Promise<boolean> {
+  if (!process.stdin.isTTY) {
+    console.error("Not a terminal; pass --yes to proceed without confirmation.");
+    return false;
+  }
+
+  const rl = readline.createInterface({
+    input: process.stdin,
+    output: process.stdout,
+  });
+  const answer = await new Promise<string>((resolve) =>
+    rl.question(`${question} [y/N] `, resolve)
+  );
+  rl.close();
+  return /^y(es)?$/i.test(answer.trim());
+}
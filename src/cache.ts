@@ -0,0 +1,118 @@
+import Conf from "conf";
+import type { Profile } from "./github";
+import { settings } from "./settings";
+
+// One page of a relation list as last returned by GitHub, kept so the next
+// fetch can be made conditional.
+export interface CachedPage {
+  etag?: string;
+  lastModified?: string;
+  hasNext: boolean;
+  logins: string[];
+}
+
+export type Schema = {
+  followers: {
+    lastUpdate: number;
+    data: string[];
+  };
+  followings: {
+    lastUpdate: number;
+    data: string[];
+  };
+  followersPages: CachedPage[];
+  followingsPages: CachedPage[];
+};
+
+export interface CachedUser {
+  lastUpdate: number;
+  data: Profile;
+  // When the user was last seen active on GitHub, looked up separately.
+  activity?: {
+    lastUpdate: number;
+    lastActive: string | null;
+  };
+}
+
+export type UserSchema = Record<string, CachedUser>;
+
+// A namespace keeps a separate store per analysed account.
+export const getConfig = <T extends Record<string, any> = Schema>(
+  configName: string,
+  namespace?: string
+) =>
+  new Conf<T>({
+    projectName: "github-social",
+    configName:
+      namespace === undefined
+        ? configName
+        : `${configName}-${namespace.toLowerCase()}`,
+  });
+export const cacheForRelations = (login = settings.user) =>
+  getConfig("relationsCache", login);
+export const cacheForUsers = () => getConfig<UserSchema>("userCache");
+
+// Local notes on accounts blocked through the tool, or found blocked by
+// `blocked`, keyed by lowercased login.
+export interface BlockNote {
+  timestamp: number;
+  reason?: string;
+}
+
+export const blockStore = () => getConfig<Record<string, BlockNote>>("blocks");
+
+export function markBlocked(login: string, reason?: string) {
+  const store = blockStore();
+  const previous = store.get(login.toLowerCase()) as BlockNote | undefined;
+  store.set(login.toLowerCase(), {
+    timestamp: previous?.timestamp ?? Date.now(),
+    reason: reason ?? previous?.reason,
+  });
+}
+
+export const blockedLogins = () =>
+  new Set(Object.keys(blockStore().store).map((login) => login.toLowerCase()));
+
+// Personal tags and free-form notes on any account, keyed by lowercased login.
+export interface Annotation {
+  tags: string[];
+  notes: { timestamp: number; text: string }[];
+}
+
+export const annotationStore = () =>
+  getConfig<Record<string, Annotation>>("annotations");
+
+export function annotationOf(login: string): Annotation {
+  return (
+    (annotationStore().get(login.toLowerCase()) as Annotation | undefined) ?? {
+      tags: [],
+      notes: [],
+    }
+  );
+}
+
+// Stores the annotation, dropping the entry once nothing is left in it.
+export function setAnnotation(login: string, annotation: Annotation) {
+  const store = annotationStore();
+  if (annotation.tags.length === 0 && annotation.notes.length === 0) {
+    store.delete(login.toLowerCase());
+  } else {
+    store.set(login.toLowerCase(), annotation);
+  }
+}
+
+export function normalizeTag(name: string): string {
+  return name.trim().toLowerCase();
+}
+
+// Adds tags to an account and returns all of its tags.
+export function addTags(login: string, tags: string[]): string[] {
+  const annotation = annotationOf(login);
+  for (const name of tags.map(normalizeTag)) {
+    if (name !== "" && !annotation.tags.includes(name)) {
+      annotation.tags.push(name);
+    }
+  }
+  setAnnotation(login, annotation);
+  return annotation.tags;
+}
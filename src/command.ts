@@ -0,0 +1,30 @@
+import { Args, formatOptions, Options } from "./args";
+import { settingsOptions } from "./settings";
+
+export interface Command {
+  name: string;
+  summary: string;
+  usage: string;
+  options: Options;
+  // How many positional arguments the command takes; 0 when not given.
+  maxPositionals?: number;
+  // Resolves to the process exit code; `undefined` means success.
+  run(args: Args): Promise<number | void>;
+}
+
+export const commonOptions: Options = {
+  ...settingsOptions,
+  help: { type: "boolean", alias: "h", description: "Show this help" },
+};
+
+export function optionsFor(command: Command): Options {
+  return { ...command.options, ...commonOptions };
+}
+
+export function formatHelp(command: Command): string {
+  return [
+    `Usage: github-social ${command.usage}`,
+    command.summary,
+    `Options:\n${formatOptions(optionsFor(command))}`,
+  ].join("\n\n");
+}
@@ -0,0 +1,96 @@
+import chalk from "chalk";
+import Table from "cli-table";
+import { UsageError } from "../args";
+import { applyToLogins } from "../bulk";
+import { blockStore, markBlocked } from "../cache";
+import { Command } from "../command";
+import { assertOwnNetwork, fetchBlocked, getToken } from "../github";
+import { formatDate } from "../util";
+
+export const block: Command = {
+  name: "block",
+  summary: "Block one or more users, noting why.",
+  usage: "block [options] <login...>",
+  maxPositionals: Infinity,
+  options: {
+    reason: {
+      type: "string",
+      alias: "m",
+      placeholder: "text",
+      description: "Why the users are blocked, kept in local notes",
+    },
+  },
+  async run(args) {
+    assertOwnNetwork();
+    if (args.positionals.length === 0) {
+      throw new UsageError("Missing <login> argument");
+    }
+
+    const code = await applyToLogins(args.positionals, "block", getToken());
+    const reason = args.string("reason");
+    if (reason !== undefined) {
+      for (const login of args.positionals) {
+        if (blockStore().has(login.toLowerCase())) {
+          markBlocked(login, reason);
+        }
+      }
+    }
+    return code;
+  },
+};
+
+export const unblock: Command = {
+  name: "unblock",
+  summary: "Unblock one or more users.",
+  usage: "unblock [options] <login...>",
+  maxPositionals: Infinity,
+  options: {},
+  async run(args) {
+    assertOwnNetwork();
+    if (args.positionals.length === 0) {
+      throw new UsageError("Missing <login> argument");
+    }
+    return applyToLogins(args.positionals, "unblock", getToken());
+  },
+};
+
+export const blocked: Command = {
+  name: "blocked",
+  summary: "List blocked users with the noted reasons.",
+  usage: "blocked [options]",
+  options: {},
+  async run() {
+    assertOwnNetwork();
+    const logins = await fetchBlocked(getToken());
+
+    // Keep the local notes in sync with accounts blocked elsewhere.
+    const store = blockStore();
+    const current = new Set(logins.map((login) => login.toLowerCase()));
+    for (const login of current) {
+      if (!store.has(login)) {
+        markBlocked(login);
+      }
+    }
+    for (const login of Object.keys(store.store)) {
+      if (!current.has(login)) {
+        store.delete(login);
+      }
+    }
+
+    if (logins.length === 0) {
+      console.log("no blocked users");
+      return;
+    }
+
+    const table = new Table({ head: ["login", "since", "reason"] });
+    for (const login of logins.sort()) {
+      const note = store.get(login.toLowerCase());
+      table.push([
+        login,
+        formatDate(note.timestamp),
+        note.reason ?? chalk.gray("-"),
+      ]);
+    }
+    console.log(table.toString());
+  },
+};
@@ -0,0 +1,102 @@
+import fs from "fs";
+import { UsageError } from "../args";
+import { cacheForRelations, cacheForUsers } from "../cache";
+import { Command } from "../command";
+import { isFreshUser } from "../github";
+import { historyPath } from "../history";
+import { journalPath } from "../journal";
+import { listPath } from "../lists";
+import { formatDuration } from "../util";
+
+const targets = ["relations", "users"] as const;
+
+export const cache: Command = {
+  name: "cache",
+  summary: "Inspect, prune or clear the local caches.",
+  usage: "cache <path|stats|prune|clear> [relations|users]",
+  maxPositionals: 2,
+  options: {},
+  async run(args) {
+    const [action, target] = args.positionals;
+    if (
+      target !== undefined &&
+      !(targets as readonly string[]).includes(target)
+    ) {
+      throw new UsageError(`Unknown cache: ${target}`);
+    }
+    const relationsCache = cacheForRelations();
+    const userCache = cacheForUsers();
+    const stores = [
+      ...(target !== "users" ? [relationsCache] : []),
+      ...(target !== "relations" ? [userCache] : []),
+    ];
+
+    switch (action) {
+      case "path":
+        for (const store of stores) {
+          console.log(store.path);
+        }
+        if (target === undefined) {
+          console.log(journalPath());
+          console.log(historyPath());
+          console.log(listPath("allowlist"));
+          console.log(listPath("denylist"));
+        }
+        return;
+      case "stats": {
+        const users = Object.values(userCache.store);
+        const fresh = users.filter(isFreshUser).length;
+        if (target !== "users") {
+          for (const key of ["followers", "followings"] as const) {
+            const entry = relationsCache.get(key);
+            console.log(
+              entry
+                ? `${key}: ${entry.data.length} logins, fetched ${formatDuration(
+                    Date.now() - entry.lastUpdate
+                  )} ago`
+                : `${key}: not cached`
+            );
+          }
+        }
+        if (target !== "relations") {
+          console.log(
+            `users: ${users.length} profiles, ${fresh} fresh, ${
+              users.length - fresh
+            } stale`
+          );
+        }
+        for (const store of stores) {
+          const size = fs.existsSync(store.path)
+            ? fs.statSync(store.path).size
+            : 0;
+          console.log(`${store.path}: ${size} bytes`);
+        }
+        return;
+      }
+      case "prune": {
+        if (target === "relations") {
+          throw new UsageError("Only the users cache can be pruned");
+        }
+        let pruned = 0;
+        for (const [login, entry] of Object.entries(userCache.store)) {
+          if (!isFreshUser(entry)) {
+            userCache.delete(login);
+            pruned++;
+          }
+        }
+        console.log(`pruned ${pruned} stale profiles`);
+        return;
+      }
+      case "clear":
+        for (const store of stores) {
+          store.clear();
+        }
+        console.log("cache cleared");
+        return;
+      case undefined:
+        throw new UsageError("Missing cache action");
+      default:
+        throw new UsageError(`Unknown cache action: ${action}`);
+    }
+  },
+};
@@ -0,0 +1,76 @@
+import { UsageError } from "../args";
+import { Command } from "../command";
+import { getRelations, getToken } from "../github";
+import { difference, intersect } from "../util";
+
+export const compare: Command = {
+  name: "compare",
+  summary: "Show how the networks of two users overlap.",
+  usage: "compare [options] <loginA> <loginB>",
+  maxPositionals: 2,
+  options: {
+    counts: {
+      type: "boolean",
+      alias: "c",
+      description: "Only print the number of accounts in each section",
+    },
+  },
+  async run(args) {
+    const [a, b] = args.positionals;
+    if (a === undefined || b === undefined) {
+      throw new UsageError("Expected exactly two logins");
+    }
+
+    const token = getToken();
+    const relationsA = await getRelations(token, a);
+    const relationsB = await getRelations(token, b);
+
+    const sections: [string, Set<string>][] = [
+      [
+        "shared followers",
+        intersect(relationsA.followers, relationsB.followers),
+      ],
+      [
+        "shared followings",
+        intersect(relationsA.followings, relationsB.followings),
+      ],
+      [
+        `followed by ${a} only`,
+        difference(relationsA.followings, relationsB.followings),
+      ],
+      [
+        `followed by ${b} only`,
+        difference(relationsB.followings, relationsA.followings),
+      ],
+    ];
+
+    for (const [label, logins] of sections) {
+      console.log(`${label} (${logins.size})`);
+      if (!args.boolean("counts")) {
+        for (const login of [...logins].sort()) {
+          console.log(`  ${login}`);
+        }
+      }
+    }
+
+    console.log(
+      `followers similarity: ${jaccard(
+        relationsA.followers,
+        relationsB.followers
+      ).toFixed(3)}`
+    );
+    console.log(
+      `followings similarity: ${jaccard(
+        relationsA.followings,
+        relationsB.followings
+      ).toFixed(3)}`
+    );
+  },
+};
+
+// Size of the intersection over size of the union; 1 for identical sets.
+function jaccard<T>(lhs: Set<T>, rhs: Set<T>): number {
+  const shared = intersect(lhs, rhs).size;
+  const union = lhs.size + rhs.size - shared;
+  return union === 0 ? 0 : shared / union;
+}
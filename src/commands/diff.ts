@@ -0,0 +1,141 @@
+import chalk from "chalk";
+import Table from "cli-table";
+import { UsageError } from "../args";
+import { cacheForRelations } from "../cache";
+import { Command } from "../command";
+import { fetchFollowers, fetchFollowings, getToken } from "../github";
+import {
+  readSnapshots,
+  snapshotAt,
+  SnapshotKind,
+  snapshotTime,
+} from "../history";
+import { difference, formatDate, parseDate } from "../util";
+
+type Side = Record<SnapshotKind, Set<string>>;
+
+export const diff: Command = {
+  name: "diff",
+  summary:
+    "Show who started or stopped following you, and whom you started or stopped following.",
+  usage: "diff [options] [<from> [<to>]]",
+  maxPositionals: 2,
+  options: {
+    since: {
+      type: "string",
+      placeholder: "date",
+      description: "Compare the network at this date with now",
+    },
+    list: {
+      type: "boolean",
+      alias: "l",
+      description: "List recorded snapshots",
+    },
+  },
+  async run(args) {
+    if (args.boolean("list")) {
+      listSnapshots();
+      return;
+    }
+
+    const since = args.string("since");
+    const [from, to] = args.positionals;
+    if (since !== undefined && to !== undefined) {
+      throw new UsageError("Too many snapshots given");
+    }
+    if (since !== undefined && from !== undefined) {
+      throw new UsageError("--since cannot be combined with a <from> snapshot");
+    }
+
+    // With no <from>, compare against what was cached before this fetch.
+    const cache = cacheForRelations();
+    const before =
+      since !== undefined || from !== undefined
+        ? sideAt(since ?? from)
+        : {
+            followers: new Set(cache.get("followers")?.data ?? []),
+            followings: new Set(cache.get("followings")?.data ?? []),
+          };
+    const after = to !== undefined ? sideAt(to) : await fetchCurrent();
+
+    printChanges(
+      "new followers",
+      difference(after.followers, before.followers),
+      chalk.green
+    );
+    printChanges(
+      "no longer followed you",
+      difference(before.followers, after.followers),
+      chalk.red
+    );
+    printChanges(
+      "started following",
+      difference(after.followings, before.followings),
+      chalk.green
+    );
+    printChanges(
+      "stopped following",
+      difference(before.followings, after.followings),
+      chalk.red
+    );
+  },
+};
+
+async function fetchCurrent(): Promise<Side> {
+  const token = getToken();
+  const cache = cacheForRelations();
+  const followers = await fetchFollowers(token);
+  const followings = await fetchFollowings(token);
+  cache.set("followers", { lastUpdate: Date.now(), data: followers });
+  cache.set("followings", { lastUpdate: Date.now(), data: followings });
+  return {
+    followers: new Set(followers),
+    followings: new Set(followings),
+  };
+}
+
+// A reference is either a snapshot id or a date.
+function sideAt(ref: string): Side {
+  const timestamp = snapshotTime(ref) ?? parseDate(ref);
+  if (timestamp === undefined) {
+    throw new UsageError(`Unknown snapshot or invalid date: '${ref}'`);
+  }
+  const side = (kind: SnapshotKind) => {
+    const snapshot = snapshotAt(kind, timestamp);
+    if (snapshot === undefined) {
+      throw new Error(`No ${kind} snapshot recorded as of ${ref}`);
+    }
+    return new Set(snapshot.logins);
+  };
+  return { followers: side("followers"), followings: side("followings") };
+}
+
+function listSnapshots() {
+  const table = new Table({ head: ["id", "date", "kind", "logins"] });
+  const snapshots = [
+    ...readSnapshots("followers"),
+    ...readSnapshots("followings"),
+  ].sort((a, b) => a.timestamp - b.timestamp);
+  for (const snapshot of snapshots) {
+    table.push([
+      snapshot.id,
+      `${formatDate(snapshot.timestamp)} ${new Date(
+        snapshot.timestamp
+      ).toLocaleTimeString()}`,
+      snapshot.kind,
+      snapshot.logins.length,
+    ]);
+  }
+  console.log(table.toString());
+}
+
+export function printChanges(
+  label: string,
+  logins: Set<string>,
+  color: chalk.Chalk
+) {
+  console.log(`${label} (${logins.size}):`);
+  for (const login of [...logins].sort()) {
+    console.log(`  ${color(login)}`);
+  }
+}
@@ -0,0 +1,120 @@
+import { Args } from "../args";
+import { applyToLogins, confirm } from "../bulk";
+import { Command } from "../command";
+import { assertOwnNetwork, getRelations, getToken } from "../github";
+import { loadList } from "../lists";
+import {
+  buildRows,
+  classify,
+  renderTable,
+  Row,
+  tagFilter,
+  tagOptions,
+} from "../report";
+import { globToRegExp } from "../util";
+
+// GitHub flags accounts that follow too many users in a short time, so a
+// single run never follows more than this unless told otherwise.
+const DEFAULT_MAX = 50;
+
+export const followBack: Command = {
+  name: "follow-back",
+  summary: "Follow back accounts that follow you but you don't follow.",
+  usage: "follow-back [options]",
+  options: {
+    top: {
+      type: "number",
+      placeholder: "n",
+      description: "Only follow back the n accounts with the highest impact",
+    },
+    "min-repos": {
+      type: "number",
+      placeholder: "n",
+      description: "Only follow back accounts with at least n public repos",
+    },
+    "min-impact": {
+      type: "number",
+      placeholder: "n",
+      description: "Only follow back accounts with an impact of at least n",
+    },
+    match: {
+      type: "list",
+      placeholder: "glob",
+      description: "Only follow back logins matching one of the patterns",
+    },
+    "include-suspicious": {
+      type: "boolean",
+      description: "Also follow back accounts that look like spam",
+    },
+    ...tagOptions,
+    max: {
+      type: "number",
+      placeholder: "n",
+      description: `Follow at most n accounts per run (default: ${DEFAULT_MAX})`,
+    },
+    "dry-run": {
+      type: "boolean",
+      alias: "n",
+      description: "Show the plan without following anyone",
+    },
+    yes: {
+      type: "boolean",
+      alias: "y",
+      description: "Skip the confirmation prompt",
+    },
+  },
+  async run(args) {
+    assertOwnNetwork();
+    const token = getToken();
+    const { watcher } = classify(await getRelations(token));
+    const isDenied = await loadList("denylist", token);
+    const candidates = (await buildRows(watcher, "watcher", token))
+      .filter((row) => !isDenied(row.login))
+      .filter(selectFilter(args));
+
+    const max = Math.min(
+      args.number("top") ?? Infinity,
+      args.number("max") ?? DEFAULT_MAX
+    );
+    const rows = candidates.slice(0, max);
+
+    if (rows.length === 0) {
+      console.log("nothing to follow back");
+      return;
+    }
+
+    console.log(renderTable(rows));
+    console.log(`${rows.length} accounts will be followed.`);
+    if (candidates.length > rows.length) {
+      console.log(
+        `${candidates.length - rows.length} more matched but were left for a later run.`
+      );
+    }
+
+    if (args.boolean("dry-run")) {
+      return;
+    }
+    if (!args.boolean("yes") && !(await confirm("Proceed?"))) {
+      console.log("aborted");
+      return 1;
+    }
+
+    return applyToLogins(rows.map((row) => row.login), "follow", token);
+  },
+};
+
+function selectFilter(args: Args): (row: Row) => boolean {
+  const minRepos = args.number("min-repos");
+  const minImpact = args.number("min-impact");
+  const includeSuspicious = args.boolean("include-suspicious");
+  const patterns = args.list("match").map(globToRegExp);
+
+  const tagged = tagFilter(args);
+
+  return (row) =>
+    tagged(row) &&
+    (minRepos === undefined || row.repos >= minRepos) &&
+    (minImpact === undefined || row.impact >= minImpact) &&
+    (includeSuspicious || row.suspicious.length === 0) &&
+    (patterns.length === 0 || patterns.some((re) => re.test(row.login)));
+}
@@ -0,0 +1,19 @@
+import { UsageError } from "../args";
+import { applyToLogins } from "../bulk";
+import { Command } from "../command";
+import { assertOwnNetwork, getToken } from "../github";
+
+export const follow: Command = {
+  name: "follow",
+  summary: "Follow one or more users.",
+  usage: "follow [options] <login...>",
+  maxPositionals: Infinity,
+  options: {},
+  async run(args) {
+    assertOwnNetwork();
+    if (args.positionals.length === 0) {
+      throw new UsageError("Missing <login> argument");
+    }
+    return applyToLogins(args.positionals, "follow", getToken());
+  },
+};
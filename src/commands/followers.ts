@@ -0,0 +1,50 @@
+import { Command } from "../command";
+import { getRelations, getToken } from "../github";
+
+export const followers: Command = {
+  name: "followers",
+  summary: "List accounts following you. Mutuals are marked with '*'.",
+  usage: "followers [options]",
+  options: {
+    count: {
+      type: "boolean",
+      alias: "c",
+      description: "Only print the number of followers",
+    },
+  },
+  async run(args) {
+    const { followers, followings } = await getRelations(getToken());
+    printLogins(followers, followings, args.boolean("count"));
+  },
+};
+
+export const followings: Command = {
+  name: "followings",
+  summary: "List accounts you follow. Mutuals are marked with '*'.",
+  usage: "followings [options]",
+  options: {
+    count: {
+      type: "boolean",
+      alias: "c",
+      description: "Only print the number of followings",
+    },
+  },
+  async run(args) {
+    const { followers, followings } = await getRelations(getToken());
+    printLogins(followings, followers, args.boolean("count"));
+  },
+};
+
+function printLogins(
+  logins: Set<string>,
+  other: Set<string>,
+  countOnly: boolean
+) {
+  if (countOnly) {
+    console.log(logins.size);
+    return;
+  }
+  for (const login of [...logins].sort()) {
+    console.log(other.has(login) ? `${login} *` : login);
+  }
+}
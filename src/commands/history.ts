@@ -0,0 +1,133 @@
+import chalk from "chalk";
+import Table from "cli-table";
+import { Args, UsageError } from "../args";
+import { Command } from "../command";
+import { changesOf, readSnapshots, Snapshot } from "../history";
+import { difference, formatDate, formatWeek, parseDate } from "../util";
+
+export const history: Command = {
+  name: "history",
+  summary:
+    "Show gains and losses of followers (or followings) over time, from recorded snapshots.",
+  usage: "history [options]",
+  options: {
+    followings: {
+      type: "boolean",
+      description: "Show your followings instead of your followers",
+    },
+    by: {
+      type: "string",
+      placeholder: "day|week",
+      description: "Group the timeline by day or week (default: day)",
+    },
+    since: {
+      type: "string",
+      placeholder: "date",
+      description: "Only show changes from this date on, e.g. 2026-09-01",
+    },
+    login: {
+      type: "list",
+      placeholder: "logins",
+      description: "Show when these logins followed or unfollowed",
+    },
+    events: {
+      type: "boolean",
+      alias: "e",
+      description: "List every individual change instead of the timeline",
+    },
+  },
+  async run(args) {
+    const by = args.string("by") ?? "day";
+    if (by !== "day" && by !== "week") {
+      throw new UsageError(`Unknown grouping '${by}', expected day or week`);
+    }
+    const period = by === "day" ? formatDate : formatWeek;
+
+    const snapshots = window(
+      readSnapshots(args.boolean("followings") ? "followings" : "followers"),
+      args
+    );
+    if (snapshots.length < 2) {
+      console.log(
+        "not enough history yet; snapshots are recorded whenever relations are fetched"
+      );
+      return;
+    }
+
+    const logins = new Set(args.list("login").map((l) => l.toLowerCase()));
+    if (args.boolean("events") || logins.size > 0) {
+      for (const change of changesOf(snapshots)) {
+        if (logins.size > 0 && !logins.has(change.login.toLowerCase())) {
+          continue;
+        }
+        const sign =
+          change.change === "gained"
+            ? chalk.green(`+ ${change.login}`)
+            : chalk.red(`- ${change.login}`);
+        console.log(`${formatDate(change.timestamp)}  ${sign}`);
+      }
+      return;
+    }
+
+    const table = new Table({
+      head: [by, "gained", "lost", "net", "total"],
+    });
+    const periods = new Map<
+      string,
+      { gained: number; lost: number; total: number }
+    >();
+    let totalLost = 0;
+    for (let i = 1; i < snapshots.length; i++) {
+      const before = new Set(snapshots[i - 1].logins);
+      const after = new Set(snapshots[i].logins);
+      const lost = difference(before, after).size;
+      const key = period(snapshots[i].timestamp);
+      const entry = periods.get(key) ?? { gained: 0, lost: 0, total: 0 };
+      entry.gained += difference(after, before).size;
+      entry.lost += lost;
+      entry.total = after.size;
+      periods.set(key, entry);
+      totalLost += lost;
+    }
+    for (const [key, entry] of periods) {
+      table.push([
+        key,
+        `+${entry.gained}`,
+        `-${entry.lost}`,
+        signed(entry.gained - entry.lost),
+        entry.total,
+      ]);
+    }
+    console.log(table.toString());
+
+    const first = snapshots[0];
+    const last = snapshots[snapshots.length - 1];
+    const churn =
+      first.logins.length > 0 ? totalLost / first.logins.length : 0;
+    console.log(
+      `${formatDate(first.timestamp)} → ${formatDate(last.timestamp)}`
+    );
+    console.log(
+      `net growth: ${signed(last.logins.length - first.logins.length)}`
+    );
+    console.log(`churn rate: ${(churn * 100).toFixed(1)}%`);
+  },
+};
+
+// Snapshots from --since on, plus the last one before it as the baseline.
+function window(snapshots: Snapshot[], args: Args): Snapshot[] {
+  const sinceArg = args.string("since");
+  if (sinceArg === undefined) {
+    return snapshots;
+  }
+  const since = parseDate(sinceArg);
+  if (since === undefined) {
+    throw new UsageError(`Invalid date: '${sinceArg}'`);
+  }
+  const start = snapshots.findIndex((snapshot) => snapshot.timestamp >= since);
+  return start === -1 ? [] : snapshots.slice(Math.max(0, start - 1));
+}
+
+function signed(n: number): string {
+  return n > 0 ? `+${n}` : String(n);
+}
@@ -0,0 +1,109 @@
+import { UsageError } from "../args";
+import { Command } from "../command";
+import { formats, parseFormat, printReport } from "../format";
+import { getLastActivities, getRelations, getToken } from "../github";
+import {
+  buildRows,
+  classify,
+  columns,
+  Row,
+  tagFilter,
+  tagOptions,
+} from "../report";
+import { formatDate, parseDuration } from "../util";
+
+interface InactiveRow extends Row {
+  lastActive: string;
+  // `null` when no public activity was found at all.
+  idleDays: number | null;
+}
+
+const sortKeys = ["idle", "impact", "login"] as const;
+
+export const inactive: Command = {
+  name: "inactive",
+  summary:
+    "List accounts you follow that haven't been active on GitHub for a while.",
+  usage: "inactive [options]",
+  options: {
+    idle: {
+      type: "string",
+      placeholder: "duration",
+      description: "Minimum time since the last public activity (default: 180d)",
+    },
+    sort: {
+      type: "string",
+      placeholder: sortKeys.join("|"),
+      description: "Sort order (default: idle, longest first)",
+    },
+    format: {
+      type: "string",
+      alias: "f",
+      placeholder: formats.join("|"),
+      description: "Output format (default: table)",
+    },
+    ...tagOptions,
+  },
+  async run(args) {
+    const format = parseFormat(args.string("format"));
+    const idleArg = args.string("idle") ?? "180d";
+    const idle = parseDuration(idleArg);
+    if (idle === undefined) {
+      throw new UsageError(`Invalid duration for idle: '${idleArg}'`);
+    }
+    const sort = args.string("sort") ?? "idle";
+    if (!(sortKeys as readonly string[]).includes(sort)) {
+      throw new UsageError(
+        `Unknown sort '${sort}', expected one of: ${sortKeys.join(", ")}`
+      );
+    }
+
+    const token = getToken();
+    const relations = await getRelations(token);
+    const { mutuals, watching } = classify(relations);
+    const rows = [
+      ...(await buildRows(watching, "watching", token)),
+      ...(await buildRows(mutuals, "mutual", token)),
+    ].filter(tagFilter(args));
+
+    console.error(`Looking up activity of ${rows.length} accounts`);
+    const now = Date.now();
+    const activity = await getLastActivities(
+      rows.map((row) => row.login),
+      token
+    );
+    const inactiveRows = rows
+      .map((row) => ({ row, lastActive: activity.get(row.login) }))
+      .filter(
+        ({ lastActive }) =>
+          lastActive === undefined || now - lastActive.getTime() >= idle
+      )
+      .map<InactiveRow>(({ row, lastActive }) => ({
+        ...row,
+        lastActive: lastActive ? formatDate(lastActive.getTime()) : "never",
+        idleDays: lastActive
+          ? Math.floor((now - lastActive.getTime()) / 86400000)
+          : null,
+      }));
+
+    inactiveRows.sort((a, b) =>
+      sort === "idle"
+        ? idleDays(b) - idleDays(a)
+        : sort === "impact"
+        ? b.impact - a.impact
+        : a.login.localeCompare(b.login)
+    );
+
+    printReport(
+      format,
+      { followings: relations.followings.size, inactive: inactiveRows.length },
+      inactiveRows,
+      [...columns, "lastActive", "idleDays"]
+    );
+  },
+};
+
+// Accounts without any activity sort as the most idle ones.
+function idleDays(row: InactiveRow): number {
+  return row.idleDays ?? Number.MAX_SAFE_INTEGER;
+}
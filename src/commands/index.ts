@@ -0,0 +1,48 @@
+import { Command } from "../command";
+import { block, blocked, unblock } from "./block";
+import { cache } from "./cache";
+import { compare } from "./compare";
+import { diff } from "./diff";
+import { follow } from "./follow";
+import { followBack } from "./follow-back";
+import { followers, followings } from "./followers";
+import { history } from "./history";
+import { inactive } from "./inactive";
+import { note, notes, tag, untag } from "./notes";
+import { report } from "./report";
+import { suggest } from "./suggest";
+import { triage } from "./triage";
+import { undo } from "./undo";
+import { unfollow } from "./unfollow";
+import { user } from "./user";
+
+export const commands: Command[] = [
+  report,
+  triage,
+  followers,
+  followings,
+  diff,
+  history,
+  compare,
+  suggest,
+  inactive,
+  follow,
+  unfollow,
+  followBack,
+  undo,
+  block,
+  unblock,
+  blocked,
+  tag,
+  untag,
+  note,
+  notes,
+  cache,
+  user,
+];
+
+export const defaultCommand = report;
+
+export function findCommand(name: string): Command | undefined {
+  return commands.find((command) => command.name === name);
+}
@@ -0,0 +1,130 @@
+import chalk from "chalk";
+import Table from "cli-table";
+import { UsageError } from "../args";
+import {
+  addTags,
+  annotationOf,
+  annotationStore,
+  normalizeTag,
+  setAnnotation,
+} from "../cache";
+import { Command } from "../command";
+import { formatDate } from "../util";
+
+export const tag: Command = {
+  name: "tag",
+  summary: "Tag a user, e.g. coworker or rustconf.",
+  usage: "tag [options] <login> <tag...>",
+  maxPositionals: Infinity,
+  options: {},
+  async run(args) {
+    const [login, ...tags] = loginAnd(args.positionals, "<tag>");
+    console.log(`${login}: ${addTags(login, tags).join(", ")}`);
+  },
+};
+
+export const untag: Command = {
+  name: "untag",
+  summary: "Remove tags from a user.",
+  usage: "untag [options] <login> <tag...>",
+  maxPositionals: Infinity,
+  options: {},
+  async run(args) {
+    const [login, ...tags] = loginAnd(args.positionals, "<tag>");
+    const removed = tags.map(normalizeTag);
+    const annotation = annotationOf(login);
+    annotation.tags = annotation.tags.filter((name) => !removed.includes(name));
+    setAnnotation(login, annotation);
+    console.log(`${login}: ${annotation.tags.join(", ") || "no tags"}`);
+  },
+};
+
+export const note: Command = {
+  name: "note",
+  summary: "Add a note on a user, or remove one with --remove.",
+  usage:
+    "note [options] <login> <text...>\n       github-social note --remove <n> <login>",
+  maxPositionals: Infinity,
+  options: {
+    remove: {
+      type: "number",
+      alias: "d",
+      placeholder: "n",
+      description: "Remove the n-th note as numbered by `notes`",
+    },
+  },
+  async run(args) {
+    const remove = args.number("remove");
+    if (remove !== undefined) {
+      const [login] = loginAnd(args.positionals);
+      const annotation = annotationOf(login);
+      if (remove < 1 || remove > annotation.notes.length) {
+        throw new UsageError(`${login} has no note ${remove}`);
+      }
+      annotation.notes.splice(remove - 1, 1);
+      setAnnotation(login, annotation);
+      return;
+    }
+
+    const [login, ...words] = loginAnd(args.positionals, "<text>");
+    const annotation = annotationOf(login);
+    annotation.notes.push({ timestamp: Date.now(), text: words.join(" ") });
+    setAnnotation(login, annotation);
+  },
+};
+
+export const notes: Command = {
+  name: "notes",
+  summary: "List tags and notes, for all users or the given ones.",
+  usage: "notes [options] [login...]",
+  maxPositionals: Infinity,
+  options: {
+    tag: {
+      type: "list",
+      placeholder: "tags",
+      description: "Only list users tagged with one of these",
+    },
+  },
+  async run(args) {
+    const only = args.positionals.map((login) => login.toLowerCase());
+    const tags = args.list("tag").map(normalizeTag);
+    const entries = Object.entries(annotationStore().store)
+      .filter(([login]) => only.length === 0 || only.includes(login))
+      .filter(
+        ([, annotation]) =>
+          tags.length === 0 || annotation.tags.some((t) => tags.includes(t))
+      )
+      .sort(([a], [b]) => a.localeCompare(b));
+
+    if (entries.length === 0) {
+      console.log("no notes");
+      return;
+    }
+
+    const table = new Table({ head: ["login", "tags", "notes"] });
+    for (const [login, annotation] of entries) {
+      table.push([
+        login,
+        annotation.tags.join(", ") || chalk.gray("-"),
+        annotation.notes
+          .map(
+            (entry, i) =>
+              `${i + 1}. ${chalk.gray(formatDate(entry.timestamp))} ${entry.text}`
+          )
+          .join("\n") || chalk.gray("-"),
+      ]);
+    }
+    console.log(table.toString());
+  },
+};
+
+// A login followed by at least one more argument when `rest` is given.
+function loginAnd(positionals: string[], rest?: string): string[] {
+  if (positionals.length === 0) {
+    throw new UsageError("Missing <login> argument");
+  }
+  if (rest !== undefined && positionals.length === 1) {
+    throw new UsageError(`Missing ${rest} argument`);
+  }
+  return positionals;
+}
@@ -0,0 +1,84 @@
+import { UsageError } from "../args";
+import { Command } from "../command";
+import { applyRowOptions, rowOptions } from "../filter";
+import { formats, parseFormat, printReport } from "../format";
+import { getRelations, getToken, relationsLastUpdate } from "../github";
+import {
+  buildNetworkRows,
+  classify,
+  columns,
+  parseStatuses,
+  Status,
+  statuses,
+  tagFilter,
+  tagOptions,
+} from "../report";
+import { formatDuration } from "../util";
+
+export const report: Command = {
+  name: "report",
+  summary: "Show accounts you follow that don't follow back, and vice versa.",
+  usage: "report [options]",
+  options: {
+    format: {
+      type: "string",
+      alias: "f",
+      placeholder: formats.join("|"),
+      description: "Output format (default: table)",
+    },
+    status: {
+      type: "list",
+      placeholder: statuses.join(","),
+      description: "Sections to include (default: watching,watcher)",
+    },
+    suspicious: {
+      type: "string",
+      placeholder: "only|exclude",
+      description: "Only list, or leave out, accounts that look like spam",
+    },
+    ...tagOptions,
+    ...rowOptions,
+  },
+  async run(args) {
+    const format = parseFormat(args.string("format"));
+    const status = args.has("status")
+      ? parseStatuses(args.list("status"))
+      : (["watching", "watcher"] as Status[]);
+    const suspicious = args.string("suspicious");
+    if (
+      suspicious !== undefined &&
+      suspicious !== "only" &&
+      suspicious !== "exclude"
+    ) {
+      throw new UsageError(
+        `Unknown value for --suspicious: '${suspicious}', expected only or exclude`
+      );
+    }
+    const token = getToken();
+    const relations = await getRelations(token);
+    const { followers, followings } = relations;
+    const network = classify(relations);
+    const { mutuals, watching, watcher } = network;
+
+    const summary = {
+      followings: followings.size,
+      followers: followers.size,
+      mutuals: mutuals.length,
+      watching: watching.length,
+      watchers: watcher.length,
+      cacheAge: formatDuration(
+        Date.now() - (relationsLastUpdate() ?? Date.now())
+      ),
+    };
+
+    const rows = (await buildNetworkRows(network, status, token))
+      .filter(
+        (row) =>
+          suspicious === undefined ||
+          (row.suspicious.length > 0) === (suspicious === "only")
+      )
+      .filter(tagFilter(args));
+
+    printReport(format, summary, applyRowOptions(rows, args, columns));
+  },
+};
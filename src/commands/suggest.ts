@@ -0,0 +1,133 @@
+import Table from "cli-table";
+import { Command } from "../command";
+import {
+  fetchFollowingsOf,
+  getRelations,
+  getToken,
+  getUsers,
+  getViewerLogin,
+} from "../github";
+import { classify } from "../report";
+import { activeScorer } from "../score";
+import { settings } from "../settings";
+
+interface Suggestion {
+  login: string;
+  followedBy: string[];
+  impact: number;
+  url: string;
+}
+
+export const suggest: Command = {
+  name: "suggest",
+  summary:
+    "Suggest accounts to follow, ranked by how many of your mutuals follow them.",
+  usage: "suggest [options]",
+  options: {
+    budget: {
+      type: "number",
+      placeholder: "n",
+      description: "Walk the followings of at most n mutuals (default: 50)",
+    },
+    "max-followings": {
+      type: "number",
+      placeholder: "n",
+      description:
+        "Skip mutuals following more than n accounts (default: 1000)",
+    },
+    limit: {
+      type: "number",
+      alias: "n",
+      placeholder: "n",
+      description: "Number of suggestions to show (default: 20)",
+    },
+  },
+  async run(args) {
+    const budget = args.number("budget") ?? 50;
+    const maxFollowings = args.number("max-followings") ?? 1000;
+    const limit = args.number("limit") ?? 20;
+
+    const token = getToken();
+    const relations = await getRelations(token);
+    const self = settings.user ?? (await getViewerLogin(token));
+    const { mutuals } = classify(relations);
+
+    // Spend the budget on the mutuals with the most telling followings:
+    // those that follow a manageable number of people.
+    const profiles = await getUsers(mutuals, token);
+    const walked = mutuals
+      .filter((login) => {
+        const following = profiles.get(login)?.following ?? 0;
+        return following > 0 && following <= maxFollowings;
+      })
+      .sort(
+        (a, b) =>
+          (profiles.get(a)?.following ?? 0) - (profiles.get(b)?.following ?? 0)
+      )
+      .slice(0, budget);
+
+    // Walked lists are only needed for this run, so they stay out of the
+    // caches.
+    const followingsOf = await Promise.all(
+      walked.map((mutual) => fetchFollowingsOf(token, mutual))
+    );
+    const followedBy = new Map<string, string[]>();
+    walked.forEach((mutual, i) => {
+      for (const login of followingsOf[i]) {
+        if (login === self || relations.followings.has(login)) continue;
+        followedBy.set(login, [...(followedBy.get(login) ?? []), mutual]);
+      }
+    });
+
+    // Only the strongest candidates by mutual count get their profile fetched.
+    const candidates = [...followedBy.entries()]
+      .sort((a, b) => b[1].length - a[1].length)
+      .slice(0, limit * 3);
+    const candidateProfiles = await getUsers(
+      candidates.map(([login]) => login),
+      token
+    );
+
+    const scorer = activeScorer();
+    const suggestions: Suggestion[] = candidates
+      .map(([login, mutualLogins]) => {
+        const profile = candidateProfiles.get(login);
+        return {
+          login,
+          followedBy: mutualLogins,
+          impact: profile ? scorer.score(profile) : 0,
+          url: profile?.html_url ?? `https://github.com/${login}`,
+        };
+      })
+      .sort(
+        (a, b) =>
+          b.followedBy.length - a.followedBy.length || b.impact - a.impact
+      )
+      .slice(0, limit);
+
+    console.error(`walked the followings of ${walked.length} mutuals`);
+    if (suggestions.length === 0) {
+      console.log("no suggestions");
+      return;
+    }
+
+    const table = new Table({
+      head: ["login", "mutuals", "impact", "followed by", "url"],
+    });
+    table.push(
+      ...suggestions.map((suggestion) => [
+        suggestion.login,
+        suggestion.followedBy.length,
+        suggestion.impact.toFixed(2),
+        evidence(suggestion.followedBy),
+        suggestion.url,
+      ])
+    );
+    console.log(table.toString());
+  },
+};
+
+function evidence(logins: string[]): string {
+  const shown = logins.slice(0, 3).join(", ");
+  return logins.length > 3 ? `${shown} and ${logins.length - 3} more` : shown;
+}
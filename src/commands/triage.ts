@@ -0,0 +1,54 @@
+import { Command } from "../command";
+import { compileFilter } from "../filter";
+import { assertOwnNetwork, getRelations, getToken } from "../github";
+import {
+  buildNetworkRows,
+  classify,
+  columns,
+  parseStatuses,
+  statuses,
+  tagFilter,
+  tagOptions,
+} from "../report";
+import { Triage } from "../tui";
+
+export const triage: Command = {
+  name: "triage",
+  summary:
+    "Browse the report interactively and follow, unfollow, block or tag from it.",
+  usage: "triage [options]",
+  options: {
+    status: {
+      type: "list",
+      placeholder: statuses.join(","),
+      description: "Sections to include (default: all of them)",
+    },
+    filter: {
+      type: "string",
+      placeholder: "expr",
+      description: "Only include rows matching expr, as in `report --filter`",
+    },
+    ...tagOptions,
+  },
+  async run(args) {
+    assertOwnNetwork();
+    const status = parseStatuses(
+      args.has("status") ? args.list("status") : [...statuses]
+    );
+    const filter = args.has("filter")
+      ? compileFilter(args.string("filter") as string, columns)
+      : () => true;
+    const tagged = tagFilter(args);
+    const token = getToken();
+
+    // Called again after every action; the relations come from the cache the
+    // action just updated.
+    const load = async () => {
+      const network = classify(await getRelations(token));
+      const rows = await buildNetworkRows(network, status, token);
+      return rows.filter((row) => tagged(row) && filter(row));
+    };
+
+    await new Triage(load, token).run();
+  },
+};
@@ -0,0 +1,95 @@
+import { UsageError } from "../args";
+import { applyToLogins, confirm } from "../bulk";
+import { Command } from "../command";
+import { assertOwnNetwork, getToken } from "../github";
+import { groupByRun, inverse, JournalEntry, readJournal } from "../journal";
+
+export const undo: Command = {
+  name: "undo",
+  summary:
+    "Reverse the follows and unfollows of the last run, or of the given run.",
+  usage: "undo [options] [run]",
+  maxPositionals: 1,
+  options: {
+    list: {
+      type: "boolean",
+      alias: "l",
+      description: "List recorded runs instead of undoing one",
+    },
+    "dry-run": {
+      type: "boolean",
+      alias: "n",
+      description: "Show the plan without changing anything",
+    },
+    yes: {
+      type: "boolean",
+      alias: "y",
+      description: "Skip the confirmation prompt",
+    },
+  },
+  async run(args) {
+    const runs = groupByRun(readJournal());
+
+    if (args.boolean("list")) {
+      for (const [run, entries] of runs) {
+        console.log(
+          `${run}  ${new Date(entries[0].timestamp).toLocaleString()}  ${summarize(entries)}`
+        );
+      }
+      return;
+    }
+
+    assertOwnNetwork();
+    const [runId] = args.positionals;
+    const entries =
+      runId !== undefined ? runs.get(runId) : [...runs.values()].pop();
+    if (entries === undefined) {
+      if (runId !== undefined) {
+        throw new UsageError(`Unknown run: ${runId}`);
+      }
+      console.log("nothing to undo");
+      return;
+    }
+
+    const plan = entries
+      .filter((entry) => entry.result === "ok")
+      .reverse()
+      .map((entry) => ({ login: entry.login, action: inverse[entry.action] }));
+
+    if (plan.length === 0) {
+      console.log(`run ${entries[0].run} made no changes`);
+      return;
+    }
+
+    for (const { login, action } of plan) {
+      console.log(`${action} ${login}`);
+    }
+    console.log(
+      `${plan.length} actions from run ${entries[0].run} will be reversed.`
+    );
+
+    if (args.boolean("dry-run")) {
+      return;
+    }
+    if (!args.boolean("yes") && !(await confirm("Proceed?"))) {
+      console.log("aborted");
+      return 1;
+    }
+
+    const token = getToken();
+    let code = 0;
+    for (const { login, action } of plan) {
+      code = Math.max(code, await applyToLogins([login], action, token));
+    }
+    return code;
+  },
+};
+
+function summarize(entries: JournalEntry[]): string {
+  const counts = new Map<string, number>();
+  for (const entry of entries) {
+    const key = entry.result === "ok" ? entry.action : "failed";
+    counts.set(key, (counts.get(key) ?? 0) + 1);
+  }
+  return [...counts].map(([key, count]) => `${key} ${count}`).join(", ");
+}
@@ -0,0 +1,132 @@
+import { Args, UsageError } from "../args";
+import { applyToLogins, confirm } from "../bulk";
+import { Command } from "../command";
+import { assertOwnNetwork, getRelations, getToken } from "../github";
+import { compile } from "../lists";
+import {
+  buildRows,
+  classify,
+  renderTable,
+  Row,
+  tagFilter,
+  tagOptions,
+} from "../report";
+import { globToRegExp, readListFile } from "../util";
+
+export const unfollow: Command = {
+  name: "unfollow",
+  summary:
+    "Unfollow the given users, or with --watching, everyone who doesn't follow you back.",
+  usage: "unfollow [options] <login...>\n       github-social unfollow --watching [options]",
+  maxPositionals: Infinity,
+  options: {
+    watching: {
+      type: "boolean",
+      description: "Unfollow accounts that don't follow you back",
+    },
+    "max-impact": {
+      type: "number",
+      placeholder: "n",
+      description: "Only unfollow accounts with an impact below n",
+    },
+    match: {
+      type: "list",
+      placeholder: "glob",
+      description: "Only unfollow logins matching one of the patterns",
+    },
+    suspicious: {
+      type: "boolean",
+      description: "Only unfollow accounts that look like spam",
+    },
+    ...tagOptions,
+    allow: {
+      type: "list",
+      placeholder: "logins",
+      description:
+        "Never unfollow these logins or patterns, on top of allowlist.txt",
+    },
+    allowlist: {
+      type: "string",
+      placeholder: "file",
+      description:
+        "Never unfollow accounts listed in file, as in allowlist.txt",
+    },
+    "dry-run": {
+      type: "boolean",
+      alias: "n",
+      description: "Show the plan without unfollowing anyone",
+    },
+    yes: {
+      type: "boolean",
+      alias: "y",
+      description: "Skip the confirmation prompt",
+    },
+  },
+  async run(args) {
+    assertOwnNetwork();
+    const token = getToken();
+
+    if (!args.boolean("watching")) {
+      if (args.positionals.length === 0) {
+        throw new UsageError("Missing <login> argument or --watching");
+      }
+      return applyToLogins(args.positionals, "unfollow", token);
+    }
+
+    if (args.positionals.length > 0) {
+      throw new UsageError("Logins cannot be combined with --watching");
+    }
+
+    const { watching } = classify(await getRelations(token));
+    const rows = (await buildRows(watching, "watching", token)).filter(
+      await selectFilter(args, token)
+    );
+
+    if (rows.length === 0) {
+      console.log("nothing to unfollow");
+      return;
+    }
+
+    console.log(renderTable(rows));
+    console.log(`${rows.length} accounts will be unfollowed.`);
+
+    if (args.boolean("dry-run")) {
+      return;
+    }
+    if (!args.boolean("yes") && !(await confirm("Proceed?"))) {
+      console.log("aborted");
+      return 1;
+    }
+
+    return applyToLogins(rows.map((row) => row.login), "unfollow", token);
+  },
+};
+
+async function selectFilter(
+  args: Args,
+  token: string
+): Promise<(row: Row) => boolean> {
+  const maxImpact = args.number("max-impact");
+  const onlySuspicious = args.boolean("suspicious");
+  const patterns = args.list("match").map(globToRegExp);
+  // Same entries as allowlist.txt: logins, globs and org:<name>.
+  const isAllowed = await compile(
+    [
+      ...args.list("allow"),
+      ...(args.has("allowlist")
+        ? readListFile(args.string("allowlist") as string)
+        : []),
+    ],
+    token
+  );
+
+  const tagged = tagFilter(args);
+
+  return (row) =>
+    tagged(row) &&
+    !row.protected &&
+    !isAllowed(row.login) &&
+    (maxImpact === undefined || row.impact < maxImpact) &&
+    (!onlySuspicious || row.suspicious.length > 0) &&
+    (patterns.length === 0 || patterns.some((re) => re.test(row.login)));
+}
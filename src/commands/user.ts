@@ -0,0 +1,49 @@
+import chalk from "chalk";
+import { UsageError } from "../args";
+import { annotationOf } from "../cache";
+import { Command } from "../command";
+import { getRelations, getToken, getUser } from "../github";
+import { activeScorer } from "../score";
+import { formatDate } from "../util";
+
+export const user: Command = {
+  name: "user",
+  summary: "Show a user's profile and their relation to you.",
+  usage: "user [options] <login>",
+  maxPositionals: 1,
+  options: {},
+  async run(args) {
+    const [login] = args.positionals;
+    if (login === undefined) {
+      throw new UsageError("Missing <login> argument");
+    }
+
+    const token = getToken();
+    const profile = await getUser(login, token);
+    const { followers, followings } = await getRelations(token);
+
+    const relation =
+      followers.has(profile.login) && followings.has(profile.login)
+        ? chalk.cyan("mutual")
+        : followings.has(profile.login)
+        ? chalk.green("watching")
+        : followers.has(profile.login)
+        ? chalk.magenta("watcher")
+        : "none";
+
+    console.log(`login: ${profile.login}`);
+    if (profile.name) console.log(`name: ${profile.name}`);
+    console.log(`relation: ${relation}`);
+    console.log(`repos: ${profile.public_repos}`);
+    console.log(`followers: ${profile.followers}`);
+    console.log(`followings: ${profile.following}`);
+    console.log(`impact: ${activeScorer().score(profile)}`);
+    console.log(`url: ${profile.html_url}`);
+
+    const { tags, notes } = annotationOf(profile.login);
+    if (tags.length > 0) console.log(`tags: ${tags.join(", ")}`);
+    for (const entry of notes) {
+      console.log(`note: ${formatDate(entry.timestamp)} ${entry.text}`);
+    }
+  },
+};
@@ -0,0 +1,188 @@
+import { parseArgs, UsageError } from "./args";
+import {
+  applyRowOptions,
+  compileFilter,
+  compileSort,
+  rowOptions,
+} from "./filter";
+import { columns, Row } from "./report";
+
+function row(fields: Partial<Row>): Row {
+  return {
+    status: "watcher",
+    login: "octocat",
+    repos: 0,
+    followers: 0,
+    followings: 0,
+    impact: 0,
+    suspicious: [],
+    tags: [],
+    protected: false,
+    url: "https://github.com/octocat",
+    ...fields,
+  };
+}
+
+const matches = (source: string, fields: Partial<Row>) =>
+  compileFilter(source, columns)(row(fields));
+
+describe("compileFilter", () => {
+  test("README example", () => {
+    const source = "followers > 100 && repos >= 5 && status == watcher";
+    expect(matches(source, { followers: 101, repos: 5 })).toBe(true);
+    expect(matches(source, { followers: 100, repos: 5 })).toBe(false);
+    expect(matches(source, { followers: 101, repos: 4 })).toBe(false);
+    expect(
+      matches(source, { followers: 101, repos: 5, status: "mutual" })
+    ).toBe(false);
+  });
+
+  test("README example with tags and a quoted glob", () => {
+    const source = `tags == coworker || login ~ "*-dev"`;
+    expect(matches(source, { tags: ["friend", "coworker"] })).toBe(true);
+    expect(matches(source, { login: "jane-dev" })).toBe(true);
+    expect(matches(source, { login: "jane", tags: ["friend"] })).toBe(false);
+  });
+
+  test("&& binds tighter than ||", () => {
+    const source = "status == mutual || followers > 100 && repos > 10";
+    expect(matches(source, { status: "mutual" })).toBe(true);
+    expect(matches(source, { followers: 200 })).toBe(false);
+    expect(matches(source, { followers: 200, repos: 11 })).toBe(true);
+  });
+
+  test("parentheses and negation", () => {
+    const source = "(status == mutual || followers > 100) && !protected";
+    expect(matches(source, { followers: 200 })).toBe(true);
+    expect(matches(source, { followers: 200, protected: true })).toBe(false);
+    expect(matches(source, { status: "mutual", repos: 11 })).toBe(true);
+    expect(matches("!(repos > 1)", { repos: 0 })).toBe(true);
+  });
+
+  test("text compares case-insensitively", () => {
+    expect(matches("status == WATCHER", {})).toBe(true);
+    expect(matches("login != OctoCat", {})).toBe(false);
+    expect(matches("login ~ OCTO*", {})).toBe(true);
+  });
+
+  test("quoted values may contain spaces and operators", () => {
+    const tags = ["met at rustconf"];
+    expect(matches(`tags == "met at rustconf"`, { tags })).toBe(true);
+    expect(matches(`tags == 'met at rustconf'`, { tags })).toBe(true);
+    expect(matches(`tags == "a && b"`, { tags: ["a && b"] })).toBe(true);
+  });
+
+  test("list fields match when any item does", () => {
+    const tags = ["rust", "coworker"];
+    expect(matches("tags == coworker", { tags })).toBe(true);
+    expect(matches("tags ~ ru*", { tags })).toBe(true);
+    expect(matches("tags == friend", { tags })).toBe(false);
+    expect(matches("tags == friend", { tags: [] })).toBe(false);
+  });
+
+  test("bare fields test for a non-empty, non-zero or true value", () => {
+    expect(matches("suspicious", { suspicious: ["no bio"] })).toBe(true);
+    expect(matches("suspicious", {})).toBe(false);
+    expect(matches("repos", { repos: 3 })).toBe(true);
+    expect(matches("repos", {})).toBe(false);
+    expect(matches("protected", { protected: true })).toBe(true);
+  });
+
+  test("booleans and numbers are coerced from the value", () => {
+    expect(matches("protected == true", { protected: true })).toBe(true);
+    expect(matches("protected == false", {})).toBe(true);
+    expect(matches("protected == true", {})).toBe(false);
+    expect(matches("impact > 1.5", { impact: 2 })).toBe(true);
+    expect(matches("impact <= 1.5", { impact: 2 })).toBe(false);
+    expect(matches("followers == 0", {})).toBe(true);
+  });
+
+  test.each([
+    ["stars > 1", /Unknown field 'stars'/],
+    ["followers >", /expected a value at the end/],
+    ["(followers > 1", /expected '\)' at the end/],
+    ["followers > 1 repos", /near 'repos'/],
+    ["followers = 1", /near '= 1'/],
+    [`login == "octocat`, /near '"octocat'/],
+    ["", /expected a field at the end/],
+  ])("rejects %p", (source, message) => {
+    expect(() => compileFilter(source, columns)).toThrow(UsageError);
+    expect(() => compileFilter(source, columns)).toThrow(message);
+  });
+
+  test("rejects a non-numeric value for a number field", () => {
+    const predicate = compileFilter("followers > many", columns);
+    expect(() => predicate(row({}))).toThrow(
+      "Expected a number in filter, got 'many'"
+    );
+  });
+});
+
+describe("compileSort", () => {
+  const rows = [
+    row({ login: "b", followers: 1, tags: ["x", "y"] }),
+    row({ login: "c", followers: 3 }),
+    row({ login: "a", followers: 2, tags: ["x"] }),
+  ];
+  const sorted = (spec: string) =>
+    [...rows].sort(compileSort(spec, columns)).map((r) => r.login);
+
+  test("numbers descend and text ascends by default", () => {
+    expect(sorted("followers")).toEqual(["c", "a", "b"]);
+    expect(sorted("login")).toEqual(["a", "b", "c"]);
+  });
+
+  test("explicit directions", () => {
+    expect(sorted("followers:asc")).toEqual(["b", "a", "c"]);
+    expect(sorted("login:desc")).toEqual(["c", "b", "a"]);
+  });
+
+  test("lists sort by their length", () => {
+    expect(sorted("tags")).toEqual(["b", "a", "c"]);
+  });
+
+  test("missing values go last in either direction", () => {
+    type IdleRow = Row & { idleDays: number | null };
+    const idle: IdleRow[] = [
+      { ...row({ login: "never" }), idleDays: null },
+      { ...row({ login: "short" }), idleDays: 1 },
+      { ...row({ login: "long" }), idleDays: 9 },
+    ];
+    const fields: (keyof IdleRow)[] = [...columns, "idleDays"];
+    for (const [spec, expected] of [
+      ["idleDays", ["long", "short", "never"]],
+      ["idleDays:asc", ["short", "long", "never"]],
+    ] as const) {
+      expect(
+        [...idle].sort(compileSort(spec, fields)).map((r) => r.login)
+      ).toEqual(expected);
+    }
+  });
+
+  test("rejects unknown columns and directions", () => {
+    expect(() => compileSort("stars", columns)).toThrow(
+      /Unknown sort column 'stars'/
+    );
+    expect(() => compileSort("login:up", columns)).toThrow(
+      /Unknown sort direction 'up'/
+    );
+  });
+});
+
+describe("applyRowOptions", () => {
+  test("filters, then sorts, then limits", () => {
+    const rows = [
+      row({ login: "c", repos: 9 }),
+      row({ login: "a", repos: 1 }),
+      row({ login: "b", repos: 5 }),
+      row({ login: "d", repos: 7 }),
+    ];
+    const args = parseArgs(
+      ["--filter", "repos >= 5", "--sort", "login", "--limit", "2"],
+      rowOptions
+    );
+    expect(applyRowOptions(rows, args, columns).map((r) => r.login)).toEqual(
+      ["b", "c"]
+    );
+  });
+});
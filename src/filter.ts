@@ -0,0 +1,245 @@
+import { Args, Options, UsageError } from "./args";
+import { Row } from "./report";
+import { globToRegExp } from "./util";
+
+// A small expression language over row fields, e.g.
+// `followers > 100 && repos >= 5 && status == watcher`.
+//
+//   expr       := and ("||" and)*
+//   and        := unary ("&&" unary)*
+//   unary      := "!" unary | "(" expr ")" | field [op value]
+//   op         := "==" | "!=" | ">" | ">=" | "<" | "<=" | "~"
+//
+// Text compares case-insensitively and `~` matches a glob. A list field such
+// as `tags` matches when any of its items does, and a bare field tests that it
+// is non-empty, non-zero or true.
+export type Predicate<R> = (row: R) => boolean;
+
+const operators = ["==", "!=", ">=", "<=", ">", "<", "~"] as const;
+type Operator = typeof operators[number];
+
+const tokenPattern = /\s*(&&|\|\||==|!=|>=|<=|[()!<>~]|"[^"]*"|'[^']*'|[^\s()!<>=~&|"']+)/y;
+
+function tokenize(source: string): string[] {
+  const tokens: string[] = [];
+  tokenPattern.lastIndex = 0;
+  while (tokenPattern.lastIndex < source.length) {
+    const start = tokenPattern.lastIndex;
+    const match = tokenPattern.exec(source);
+    if (match === null) {
+      if (source.slice(start).trim() === "") break;
+      throw new UsageError(
+        `Invalid filter near '${source.slice(start).trim()}'`
+      );
+    }
+    tokens.push(match[1]);
+  }
+  return tokens;
+}
+
+export function compileFilter<R extends Row>(
+  source: string,
+  fields: (keyof R)[]
+): Predicate<R> {
+  const tokens = tokenize(source);
+  let pos = 0;
+
+  const peek = () => tokens[pos] as string | undefined;
+  const expect = (what: string) => {
+    const token = tokens[pos++];
+    if (token === undefined) {
+      throw new UsageError(`Invalid filter: expected ${what} at the end`);
+    }
+    return token;
+  };
+
+  function or(): Predicate<R> {
+    let left = and();
+    while (peek() === "||") {
+      pos++;
+      const [a, b] = [left, and()];
+      left = (row) => a(row) || b(row);
+    }
+    return left;
+  }
+
+  function and(): Predicate<R> {
+    let left = unary();
+    while (peek() === "&&") {
+      pos++;
+      const [a, b] = [left, unary()];
+      left = (row) => a(row) && b(row);
+    }
+    return left;
+  }
+
+  function unary(): Predicate<R> {
+    const token = expect("a field");
+    if (token === "!") {
+      const inner = unary();
+      return (row) => !inner(row);
+    }
+    if (token === "(") {
+      const inner = or();
+      if (expect("')'") !== ")") {
+        throw new UsageError(`Invalid filter: expected ')' in '${source}'`);
+      }
+      return inner;
+    }
+    if (!(fields as string[]).includes(token)) {
+      throw new UsageError(
+        `Unknown field '${token}' in filter, expected one of: ${fields.join(
+          ", "
+        )}`
+      );
+    }
+    const field = token as keyof R;
+    const op = peek();
+    if (!(operators as readonly string[]).includes(op ?? "")) {
+      return (row) => truthy(row[field]);
+    }
+    pos++;
+    const value = unquote(expect("a value"));
+    return (row) => {
+      const actual: unknown = row[field];
+      return Array.isArray(actual)
+        ? actual.some((item) => compare(item, op as Operator, value))
+        : compare(actual, op as Operator, value);
+    };
+  }
+
+  const predicate = or();
+  if (pos < tokens.length) {
+    throw new UsageError(`Invalid filter near '${tokens[pos]}'`);
+  }
+  return predicate;
+}
+
+function compare(actual: unknown, op: Operator, value: string): boolean {
+  if (actual === null || actual === undefined) {
+    return op === "!=";
+  }
+  if (op === "~") {
+    return globToRegExp(value).test(String(actual));
+  }
+  if (typeof actual === "number") {
+    const expected = Number(value);
+    if (Number.isNaN(expected)) {
+      throw new UsageError(`Expected a number in filter, got '${value}'`);
+    }
+    return ordered(actual - expected, op);
+  }
+  const expected =
+    typeof actual === "boolean" ? String(value === "true") : value;
+  return ordered(
+    String(actual).toLowerCase().localeCompare(expected.toLowerCase()),
+    op
+  );
+}
+
+function ordered(difference: number, op: Operator): boolean {
+  switch (op) {
+    case "==":
+      return difference === 0;
+    case "!=":
+      return difference !== 0;
+    case ">":
+      return difference > 0;
+    case ">=":
+      return difference >= 0;
+    case "<":
+      return difference < 0;
+    case "<=":
+      return difference <= 0;
+    default:
+      return false;
+  }
+}
+
+function truthy(value: unknown): boolean {
+  return Array.isArray(value) ? value.length > 0 : Boolean(value);
+}
+
+function unquote(token: string): string {
+  return /^(["']).*\1$/.test(token) ? token.slice(1, -1) : token;
+}
+
+// `column` or `column:asc|desc`. Numbers sort descending by default, text
+// ascending; lists sort by their length and missing values always go last.
+export function compileSort<R extends Row>(
+  spec: string,
+  fields: (keyof R)[]
+): (a: R, b: R) => number {
+  const [name, direction] = spec.split(":");
+  if (!(fields as string[]).includes(name)) {
+    throw new UsageError(
+      `Unknown sort column '${name}', expected one of: ${fields.join(", ")}`
+    );
+  }
+  if (
+    direction !== undefined &&
+    direction !== "asc" &&
+    direction !== "desc"
+  ) {
+    throw new UsageError(
+      `Unknown sort direction '${direction}', expected asc or desc`
+    );
+  }
+  const field = name as keyof R;
+  return (a, b) => {
+    const [x, y]: unknown[] = [a[field], b[field]];
+    if (missing(x) || missing(y)) {
+      return Number(missing(x)) - Number(missing(y));
+    }
+    const numeric = typeof x !== "string";
+    const difference = numeric
+      ? sortValue(x) - sortValue(y)
+      : String(x).localeCompare(String(y));
+    const descending = (direction ?? (numeric ? "desc" : "asc")) === "desc";
+    return descending ? -difference : difference;
+  };
+}
+
+function missing(value: unknown): boolean {
+  return value === null || value === undefined;
+}
+
+function sortValue(value: unknown): number {
+  return Array.isArray(value) ? value.length : Number(value);
+}
+
+export const rowOptions: Options = {
+  filter: {
+    type: "string",
+    placeholder: "expr",
+    description:
+      "Only include rows matching expr, e.g. 'followers > 100 && status == watcher'",
+  },
+  sort: {
+    type: "string",
+    placeholder: "column[:asc|desc]",
+    description: "Sort rows by a column (numbers descend by default)",
+  },
+  limit: {
+    type: "number",
+    placeholder: "n",
+    description: "Show at most n rows",
+  },
+};
+
+// Applies --filter, --sort and --limit from rowOptions.
+export function applyRowOptions<R extends Row>(
+  rows: R[],
+  args: Args,
+  fields: (keyof R)[]
+): R[] {
+  const filter = args.string("filter");
+  const sort = args.string("sort");
+  const limit = args.number("limit");
+  let result =
+    filter === undefined ? rows : rows.filter(compileFilter(filter, fields));
+  if (sort !== undefined) {
+    result = [...result].sort(compileSort(sort, fields));
+  }
+  return limit === undefined ? result : result.slice(0, limit);
+}
@@ -0,0 +1,113 @@
+import { UsageError } from "./args";
+import { cell, columns, renderTable, Row } from "./report";
+
+export const formats = [
+  "table",
+  "json",
+  "ndjson",
+  "csv",
+  "tsv",
+  "markdown",
+] as const;
+
+export type Format = typeof formats[number];
+
+export type Summary = Record<string, number | string>;
+
+export function parseFormat(value: string | undefined): Format {
+  const format = value ?? "table";
+  if (!(formats as readonly string[]).includes(format)) {
+    throw new UsageError(
+      `Unknown format '${format}', expected one of: ${formats.join(", ")}`
+    );
+  }
+  return format as Format;
+}
+
+// Prints the summary and rows in the requested format. Formats meant for
+// piping (ndjson, csv, tsv) carry rows only on stdout; the summary goes to
+// stderr so it stays visible without breaking the parser on the other end.
+export function printReport<R extends Row>(
+  format: Format,
+  summary: Summary,
+  rows: R[],
+  cols: (keyof R)[] = columns
+) {
+  switch (format) {
+    case "table":
+      printSummary(summary, console.log);
+      console.log(renderTable(rows, cols));
+      break;
+    case "json":
+      console.log(JSON.stringify({ summary, rows }, null, 2));
+      break;
+    case "ndjson":
+      printSummary(summary, console.error);
+      for (const row of rows) {
+        console.log(JSON.stringify(row));
+      }
+      break;
+    case "csv":
+      printSummary(summary, console.error);
+      console.log(delimited(rows, cols, ",", escapeCsv));
+      break;
+    case "tsv":
+      printSummary(summary, console.error);
+      console.log(
+        delimited(rows, cols, "\t", (value) =>
+          value.replace(/[\t\r\n]/g, " ")
+        )
+      );
+      break;
+    case "markdown":
+      console.log(
+        Object.entries(summary)
+          .map(([key, value]) => `- ${key}: ${value}`)
+          .join("\n")
+      );
+      console.log();
+      console.log(markdownTable(rows, cols));
+      break;
+  }
+}
+
+function printSummary(summary: Summary, print: (line: string) => void) {
+  for (const [key, value] of Object.entries(summary)) {
+    print(`${key}: ${value}`);
+  }
+}
+
+function delimited<R extends Row>(
+  rows: R[],
+  cols: (keyof R)[],
+  separator: string,
+  escape: (value: string) => string
+): string {
+  return [
+    cols.join(separator),
+    ...rows.map((row) =>
+      cols
+        .map((column) => escape(String(cell(row, column))))
+        .join(separator)
+    ),
+  ].join("\n");
+}
+
+function escapeCsv(value: string): string {
+  return /[",\r\n]/.test(value) ? `"${value.replace(/"/g, '""')}"` : value;
+}
+
+function markdownTable<R extends Row>(rows: R[], cols: (keyof R)[]): string {
+  const line = (cells: string[]) => `| ${cells.join(" | ")} |`;
+  return [
+    line(cols.map(String)),
+    line(cols.map(() => "---")),
+    ...rows.map((row) =>
+      line(
+        cols.map((column) =>
+          String(cell(row, column)).replace(/\|/g, "\\|")
+        )
+      )
+    ),
+  ].join("\n");
+}
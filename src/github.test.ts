@@ -0,0 +1,145 @@
+import { Octokit } from "@octokit/rest";
+import { CachedPage } from "./cache";
+import { fetchPages } from "./github";
+
+// An in-memory relations cache in place of the Conf file.
+const mockRelations = new Map<string, unknown>();
+
+jest.mock("./cache", () => ({
+  ...jest.requireActual("./cache"),
+  cacheForRelations: () => ({
+    get: (key: string) => mockRelations.get(key),
+    set: (key: string, value: unknown) => mockRelations.set(key, value),
+  }),
+}));
+
+function page(logins: string[], etag: string) {
+  return {
+    headers: { etag },
+    data: logins.map((login) => ({ login })),
+  };
+}
+
+function notModified() {
+  return Object.assign(new Error("Not Modified"), { status: 304 });
+}
+
+function client(request: jest.Mock): Octokit {
+  return ({ request } as unknown) as Octokit;
+}
+
+const logins = (prefix: string, count: number) =>
+  Array.from({ length: count }, (_, i) => `${prefix}${i}`);
+
+beforeEach(() => mockRelations.clear());
+
+describe("fetchPages", () => {
+  test("takes a page answered with 304 from the cache", async () => {
+    const cached: CachedPage[] = [
+      { etag: '"a"', hasNext: true, logins: logins("a", 100) },
+      { etag: '"b"', hasNext: false, logins: ["b0", "b1"] },
+    ];
+    mockRelations.set("followersPages", cached);
+    const request = jest.fn().mockRejectedValue(notModified());
+
+    const result = await fetchPages(
+      client(request),
+      "followers",
+      "followersPages",
+      undefined
+    );
+
+    expect(result).toEqual([...logins("a", 100), "b0", "b1"]);
+    expect(request).toHaveBeenCalledTimes(2);
+    expect(request).toHaveBeenNthCalledWith(
+      1,
+      "GET /user/followers",
+      expect.objectContaining({ page: 1, headers: { "if-none-match": '"a"' } })
+    );
+    expect(request).toHaveBeenNthCalledWith(
+      2,
+      "GET /user/followers",
+      expect.objectContaining({ page: 2, headers: { "if-none-match": '"b"' } })
+    );
+    expect(mockRelations.get("followersPages")).toEqual(cached);
+  });
+
+  test("asks for the next page after an unchanged full last page", async () => {
+    mockRelations.set("followersPages", [
+      { etag: '"a"', hasNext: false, logins: logins("a", 100) },
+    ]);
+    const request = jest
+      .fn()
+      .mockRejectedValueOnce(notModified())
+      .mockResolvedValueOnce(page(["new"], '"c"'));
+
+    const result = await fetchPages(
+      client(request),
+      "followers",
+      "followersPages",
+      undefined
+    );
+
+    expect(result).toEqual([...logins("a", 100), "new"]);
+    expect(request).toHaveBeenCalledTimes(2);
+    expect(request).toHaveBeenNthCalledWith(
+      2,
+      "GET /user/followers",
+      expect.objectContaining({ page: 2, headers: {} })
+    );
+    expect(mockRelations.get("followersPages")).toEqual([
+      { etag: '"a"', hasNext: false, logins: logins("a", 100) },
+      { etag: '"c"', hasNext: false, logins: ["new"] },
+    ]);
+  });
+
+  test("stops after an unchanged last page that isn't full", async () => {
+    mockRelations.set("followersPages", [
+      { etag: '"a"', hasNext: false, logins: logins("a", 99) },
+    ]);
+    const request = jest.fn().mockRejectedValue(notModified());
+
+    await fetchPages(client(request), "followers", "followersPages", undefined);
+
+    expect(request).toHaveBeenCalledTimes(1);
+  });
+
+  test("replaces the cache when page 1 changed", async () => {
+    mockRelations.set("followingsPages", [
+      { etag: '"old"', hasNext: true, logins: logins("a", 100) },
+      { etag: '"old2"', hasNext: false, logins: ["b0"] },
+    ]);
+    const request = jest.fn().mockResolvedValue(page(["x", "y"], '"new"'));
+
+    const result = await fetchPages(
+      client(request),
+      "following",
+      "followingsPages",
+      "octocat"
+    );
+
+    expect(result).toEqual(["x", "y"]);
+    expect(request).toHaveBeenCalledTimes(1);
+    expect(request).toHaveBeenCalledWith(
+      "GET /users/{username}/following",
+      expect.objectContaining({
+        username: "octocat",
+        page: 1,
+        headers: { "if-none-match": '"old"' },
+      })
+    );
+    expect(mockRelations.get("followingsPages")).toEqual([
+      { etag: '"new"', hasNext: false, logins: ["x", "y"] },
+    ]);
+  });
+
+  test("rethrows errors other than 304", async () => {
+    const request = jest
+      .fn()
+      .mockRejectedValue(Object.assign(new Error("boom"), { status: 500 }));
+
+    await expect(
+      fetchPages(client(request), "followers", "followersPages", undefined)
+    ).rejects.toThrow("boom");
+  });
+});
@@ -0,0 +1,508 @@
+import { Octokit } from "@octokit/rest";
+import { Endpoints } from "@octokit/types";
+import { UsageError } from "./args";
+import {
+  blockStore,
+  CachedPage,
+  CachedUser,
+  cacheForRelations,
+  cacheForUsers,
+  markBlocked,
+  UserSchema,
+} from "./cache";
+import { BATCH_SIZE, fetchConnection, fetchProfileBatch } from "./graphql";
+import { recordSnapshot } from "./history";
+import { schedule } from "./scheduler";
+import { settings } from "./settings";
+import { chunk, difference } from "./util";
+
+export interface Relations {
+  followers: Set<string>;
+  followings: Set<string>;
+}
+
+export type User = Exclude<
+  Endpoints["GET /users/{username}/followers"]["response"]["data"][0],
+  null
+>;
+
+// The profile fields the tool relies on; both the REST and the GraphQL loader
+// provide them.
+export type Profile = Pick<
+  Endpoints["GET /users/{username}"]["response"]["data"],
+  | "login"
+  | "name"
+  | "bio"
+  | "avatar_url"
+  | "html_url"
+  | "created_at"
+  | "public_repos"
+  | "followers"
+  | "following"
+>;
+
+export function getToken(): string {
+  const token = process.env["GITHUB_TOKEN"];
+  if (token === undefined) {
+    throw new Error("Missing GITHUB_TOKEN env var.");
+  }
+  return token;
+}
+
+// Every request goes through the shared scheduler, which bounds concurrency
+// and retries rate-limited requests.
+export function createClient(auth: string): Octokit {
+  const github = new Octokit({ auth });
+  github.hook.wrap("request", (request, options) =>
+    schedule(() => request(options))
+  );
+  return github;
+}
+
+// `login` selects whose relations to load; the authenticated user's when
+// undefined.
+export async function getFollowers(
+  auth: string,
+  login = settings.user
+): Promise<string[]> {
+  const cache = cacheForRelations(login);
+  const cachedFollowers = cache.get("followers");
+
+  if (
+    !cachedFollowers ||
+    settings.refresh ||
+    Date.now() - cachedFollowers.lastUpdate > settings.relationsTtl
+  ) {
+    const followers = await fetchFollowers(auth, login);
+
+    if (cachedFollowers && login === settings.user) {
+      const newFollowers = difference(
+        new Set(followers),
+        new Set(cachedFollowers.data)
+      );
+      const NoLongerFollowed = difference(
+        new Set(cachedFollowers.data),
+        new Set(followers)
+      );
+      console.error("new followers:", [...newFollowers].join(', '));
+      console.error("no longer followed you:", [...NoLongerFollowed].join(', '));
+    }
+
+    cache.set("followers", {
+      lastUpdate: Date.now(),
+      data: followers,
+    });
+
+    return followers;
+  }
+
+  return cachedFollowers.data;
+}
+
+export async function getFollowings(
+  auth: string,
+  login = settings.user
+): Promise<string[]> {
+  const cache = cacheForRelations(login);
+  const cachedFollowings = cache.get("followings");
+
+  if (
+    !cachedFollowings ||
+    settings.refresh ||
+    Date.now() - cachedFollowings.lastUpdate > settings.relationsTtl
+  ) {
+    const followings = await fetchFollowings(auth, login);
+
+    if (cachedFollowings && login === settings.user) {
+      const started = difference(
+        new Set(followings),
+        new Set(cachedFollowings.data)
+      );
+      const stopped = difference(
+        new Set(cachedFollowings.data),
+        new Set(followings)
+      );
+      console.error("started following:", [...started].join(', '));
+      console.error("stopped following:", [...stopped].join(', '));
+    }
+
+    cache.set("followings", {
+      lastUpdate: Date.now(),
+      data: followings,
+    });
+
+    return followings;
+  }
+
+  return cachedFollowings.data;
+}
+
+// Only the network being analysed gets history; relation lists loaded along
+// the way (e.g. by `compare` or `suggest`) are just cached.
+export async function fetchFollowers(
+  auth: string,
+  login = settings.user
+): Promise<string[]> {
+  const github = createClient(auth);
+  const followers = settings.onePass
+    ? storeProfiles(await fetchConnection(github, "followers", login))
+    : await fetchPages(github, "followers", "followersPages", login);
+  if (login === settings.user) {
+    recordSnapshot("followers", followers, login);
+  }
+  return followers;
+}
+
+export async function fetchFollowings(
+  auth: string,
+  login = settings.user
+): Promise<string[]> {
+  const github = createClient(auth);
+  const followings = settings.onePass
+    ? storeProfiles(await fetchConnection(github, "following", login))
+    : await fetchPages(github, "following", "followingsPages", login);
+  if (login === settings.user) {
+    recordSnapshot("followings", followings, login);
+  }
+  return followings;
+}
+
+const PAGE_SIZE = 100;
+
+// Logins someone follows, fetched for a one-off look and not cached: neither
+// as a relation list nor as profiles.
+export async function fetchFollowingsOf(
+  auth: string,
+  login: string
+): Promise<string[]> {
+  const github = createClient(auth);
+  const users = await github.paginate("GET /users/{username}/following", {
+    username: login,
+    per_page: PAGE_SIZE,
+  });
+  return users.map((user) => (user as User).login);
+}
+
+// Pages through a relation list with conditional requests. Each page's ETag
+// is kept in the relations cache, and a page GitHub answers with 304 Not
+// Modified (which is free of rate limit) is taken from the cache.
+export async function fetchPages(
+  github: Octokit,
+  connection: "followers" | "following",
+  pagesKey: "followersPages" | "followingsPages",
+  login: string | undefined
+): Promise<string[]> {
+  const { route, params } =
+    login === undefined
+      ? { route: `GET /user/${connection}`, params: {} }
+      : {
+          route: `GET /users/{username}/${connection}`,
+          params: { username: login },
+        };
+  const cache = cacheForRelations(login);
+  const cachedPages = cache.get(pagesKey) ?? [];
+  const pages: CachedPage[] = [];
+
+  for (let page = 1; ; page++) {
+    const cachedPage: CachedPage | undefined = cachedPages[page - 1];
+    let notModified = false;
+    const headers: Record<string, string> = {};
+    if (cachedPage?.etag) {
+      headers["if-none-match"] = cachedPage.etag;
+    } else if (cachedPage?.lastModified) {
+      headers["if-modified-since"] = cachedPage.lastModified;
+    }
+
+    try {
+      const res = await github.request(route, {
+        ...params,
+        per_page: PAGE_SIZE,
+        page,
+        headers,
+      });
+      pages.push({
+        etag: res.headers.etag,
+        lastModified: res.headers["last-modified"],
+        hasNext: /rel="next"/.test(res.headers.link ?? ""),
+        logins: (res.data as User[]).map((user) => user.login),
+      });
+    } catch (err) {
+      if (err.status !== 304 || cachedPage === undefined) throw err;
+      notModified = true;
+      pages.push(cachedPage);
+    }
+
+    // The Link header isn't covered by the ETag, so a full page that was the
+    // last one may have gained a successor even when it's unchanged.
+    const last = pages[pages.length - 1];
+    if (!last.hasNext && !(notModified && last.logins.length === PAGE_SIZE)) {
+      break;
+    }
+  }
+
+  cache.set(pagesKey, pages);
+  return ([] as string[]).concat(...pages.map((page) => page.logins));
+}
+
+export async function getRelations(
+  auth: string,
+  login = settings.user
+): Promise<Relations> {
+  return {
+    followers: new Set(await getFollowers(auth, login)),
+    followings: new Set(await getFollowings(auth, login)),
+  };
+}
+
+// When the cached relations were last fetched; the older of the two lists wins.
+export function relationsLastUpdate(): number | undefined {
+  const cache = cacheForRelations();
+  const updates = [cache.get("followers"), cache.get("followings")].map(
+    (entry) => entry?.lastUpdate
+  );
+  return updates.every((update) => update !== undefined)
+    ? Math.min(...(updates as number[]))
+    : undefined;
+}
+
+export async function getUser(
+  username: string,
+  auth: string
+): Promise<Profile> {
+  const userCache = cacheForUsers();
+  const cached = userCache.get(username) as CachedUser | undefined;
+  if (cached && isFreshUser(cached) && !settings.refresh) {
+    return cached.data;
+  }
+
+  console.error(`Fetching user profile for ${username}`);
+  const github = createClient(auth);
+  const user = (await github.users.getByUsername({ username })).data;
+  cacheProfiles([user]);
+  return user;
+}
+
+// Loads many profiles at once, in batches over GraphQL. Profiles GraphQL
+// can't resolve, or all of them when GraphQL fails or `--rest` is given, are
+// fetched one by one with `getUser`. Logins that no longer exist are left out
+// of the result.
+export async function getUsers(
+  usernames: string[],
+  auth: string
+): Promise<Map<string, Profile>> {
+  // One parse of the cache file; Conf re-reads it on every `get`.
+  const cachedUsers = cacheForUsers().store;
+  const profiles = new Map<string, Profile>();
+  const missing: string[] = [];
+
+  for (const username of usernames) {
+    const cached: CachedUser | undefined = cachedUsers[username];
+    if (cached && isFreshUser(cached) && !settings.refresh) {
+      profiles.set(username, cached.data);
+    } else {
+      missing.push(username);
+    }
+  }
+
+  if (missing.length > 0 && !settings.rest) {
+    const github = createClient(auth);
+    try {
+      await Promise.all(
+        chunk(missing, BATCH_SIZE).map(async (batch) => {
+          console.error(`Fetching ${batch.length} user profiles`);
+          const fetched = await fetchProfileBatch(github, batch);
+          cacheProfiles(fetched);
+          for (const profile of fetched) {
+            profiles.set(profile.login, profile);
+          }
+        })
+      );
+    } catch (err) {
+      console.error(`GraphQL failed, falling back to REST: ${err.message}`);
+    }
+  }
+
+  await Promise.all(
+    usernames
+      .filter((username) => !profiles.has(username))
+      .map(async (username) => {
+        try {
+          profiles.set(username, await getUser(username, auth));
+        } catch (err) {
+          // Deleted or renamed since the relations were cached.
+          if (err.status !== 404) throw err;
+          console.error(`Skipping ${username}: no such user`);
+        }
+      })
+  );
+
+  return profiles;
+}
+
+// Caches profiles that came along with a relation list and returns their logins.
+function storeProfiles(profiles: Profile[]): string[] {
+  cacheProfiles(profiles);
+  return profiles.map((profile) => profile.login);
+}
+
+// Stores fetched profiles with a single read and a single write, keeping the
+// activity looked up earlier.
+function cacheProfiles(profiles: Profile[]) {
+  if (profiles.length === 0) return;
+  const userCache = cacheForUsers();
+  const cached = userCache.store;
+  const entries: UserSchema = {};
+  for (const profile of profiles) {
+    const previous: CachedUser | undefined = cached[profile.login];
+    entries[profile.login] = {
+      ...previous,
+      lastUpdate: Date.now(),
+      data: profile,
+    };
+  }
+  userCache.set(entries);
+}
+
+// When each user last did something public on GitHub: their latest public
+// event, or failing that (events only go back 90 days) their latest push.
+// Cached alongside the profile for as long as profiles are, with one read and
+// one write of the cache for the whole list.
+export async function getLastActivities(
+  usernames: string[],
+  auth: string
+): Promise<Map<string, Date | undefined>> {
+  const userCache = cacheForUsers();
+  const cached = userCache.store;
+  const github = createClient(auth);
+  const result = new Map<string, Date | undefined>();
+  const updates: UserSchema = {};
+
+  await Promise.all(
+    usernames.map(async (username) => {
+      const entry: CachedUser | undefined = cached[username];
+      let lastActive: string | null;
+      if (
+        entry?.activity &&
+        !settings.refresh &&
+        Date.now() - entry.activity.lastUpdate <= settings.userTtl
+      ) {
+        lastActive = entry.activity.lastActive;
+      } else {
+        lastActive = await fetchLastActive(github, username);
+        if (entry) {
+          updates[username] = {
+            ...entry,
+            activity: { lastUpdate: Date.now(), lastActive },
+          };
+        }
+      }
+      result.set(
+        username,
+        lastActive === null ? undefined : new Date(lastActive)
+      );
+    })
+  );
+
+  if (Object.keys(updates).length > 0) {
+    userCache.set(updates);
+  }
+  return result;
+}
+
+async function fetchLastActive(
+  github: Octokit,
+  username: string
+): Promise<string | null> {
+  const [events, repos] = await Promise.all([
+    github.activity.listPublicEventsForUser({ username, per_page: 1 }),
+    github.repos.listForUser({ username, sort: "pushed", per_page: 1 }),
+  ]);
+  const dates = [events.data[0]?.created_at, repos.data[0]?.pushed_at]
+    .filter((date): date is string => typeof date === "string")
+    .map((date) => Date.parse(date));
+  return dates.length > 0 ? new Date(Math.max(...dates)).toISOString() : null;
+}
+
+// Entries written before profiles were timestamped have no `lastUpdate` and
+// are always considered stale.
+export function isFreshUser(entry: CachedUser): boolean {
+  return (
+    entry.lastUpdate !== undefined &&
+    Date.now() - entry.lastUpdate <= settings.userTtl
+  );
+}
+
+export async function getViewerLogin(auth: string): Promise<string> {
+  const github = createClient(auth);
+  return (await github.users.getAuthenticated()).data.login;
+}
+
+// Mutations always act on the authenticated account, so they make no sense
+// while looking at someone else's network.
+export function assertOwnNetwork() {
+  if (settings.user !== undefined) {
+    throw new UsageError(
+      "--user can't be used with commands that change relations"
+    );
+  }
+}
+
+export async function follow(username: string, auth: string): Promise<void> {
+  const github = createClient(auth);
+  await github.users.follow({ username });
+  updateCachedRelation("followings", (followings) =>
+    followings.add(username)
+  );
+}
+
+export async function unfollow(username: string, auth: string): Promise<void> {
+  const github = createClient(auth);
+  await github.users.unfollow({ username });
+  updateCachedRelation("followings", (followings) =>
+    followings.delete(username)
+  );
+}
+
+// Blocking also removes any follow between the two accounts.
+export async function block(username: string, auth: string): Promise<void> {
+  const github = createClient(auth);
+  await github.users.block({ username });
+  markBlocked(username);
+  updateCachedRelation("followers", (followers) => followers.delete(username));
+  updateCachedRelation("followings", (followings) =>
+    followings.delete(username)
+  );
+}
+
+export async function unblock(username: string, auth: string): Promise<void> {
+  const github = createClient(auth);
+  await github.users.unblock({ username });
+  blockStore().delete(username.toLowerCase());
+}
+
+export async function fetchBlocked(auth: string): Promise<string[]> {
+  const github = createClient(auth);
+  const users = await github.paginate(
+    github.users.listBlockedByAuthenticated,
+    { per_page: 100 }
+  );
+  return users.map((user) => (user as User).login);
+}
+
+// Keep the cached relations in sync with mutations so the next report
+// doesn't have to wait for the cache to expire.
+function updateCachedRelation(
+  key: "followers" | "followings",
+  update: (logins: Set<string>) => void
+) {
+  const cache = cacheForRelations();
+  const cached = cache.get(key);
+  if (!cached) return;
+
+  const logins = new Set(cached.data);
+  update(logins);
+  cache.set(key, {
+    lastUpdate: cached.lastUpdate,
+    data: [...logins],
+  });
+}
@@ -0,0 +1,117 @@
+import { Octokit } from "@octokit/rest";
+import type { Profile } from "./github";
+
+// GitHub caps the nodes a single query may touch, and 100 users with a
+// handful of counts each stays well below it.
+export const BATCH_SIZE = 100;
+
+// Fields of a GraphQL `User` that map onto `Profile`.
+export const profileFields = `
+  login
+  name
+  bio
+  avatarUrl
+  url
+  createdAt
+  followers { totalCount }
+  following { totalCount }
+  repositories(privacy: PUBLIC, ownerAffiliations: [OWNER]) { totalCount }
+`;
+
+export interface GraphQLUser {
+  login: string;
+  name: string | null;
+  bio: string | null;
+  avatarUrl: string;
+  url: string;
+  createdAt: string;
+  followers: { totalCount: number };
+  following: { totalCount: number };
+  repositories: { totalCount: number };
+}
+
+export function toProfile(user: GraphQLUser): Profile {
+  return {
+    login: user.login,
+    name: user.name,
+    bio: user.bio,
+    avatar_url: user.avatarUrl,
+    html_url: user.url,
+    created_at: user.createdAt,
+    public_repos: user.repositories.totalCount,
+    followers: user.followers.totalCount,
+    following: user.following.totalCount,
+  };
+}
+
+// Fetches profiles for up to BATCH_SIZE logins in one query. Logins that no
+// longer resolve to a user are missing from the result.
+export async function fetchProfileBatch(
+  github: Octokit,
+  logins: string[]
+): Promise<Profile[]> {
+  const query = `query {
+    ${logins
+      .map(
+        (login, i) =>
+          `u${i}: user(login: ${JSON.stringify(login)}) { ${profileFields} }`
+      )
+      .join("\n")}
+  }`;
+
+  let data: Record<string, GraphQLUser | null>;
+  try {
+    data = await github.graphql<Record<string, GraphQLUser | null>>(query);
+  } catch (err) {
+    // Unknown logins fail the query but still come back as null aliases.
+    if (!err.data) throw err;
+    data = err.data;
+  }
+
+  return Object.values(data)
+    .filter((user): user is GraphQLUser => user !== null)
+    .map(toProfile);
+}
+
+// Pages through the followers or following of `login` (the viewer by
+// default), profiles included, so a whole relation list costs one query per
+// 100 accounts.
+export async function fetchConnection(
+  github: Octokit,
+  connection: "followers" | "following",
+  login?: string
+): Promise<Profile[]> {
+  const owner =
+    login === undefined
+      ? "viewer"
+      : `viewer: user(login: ${JSON.stringify(login)})`;
+  const query = `query ($cursor: String) {
+    ${owner} {
+      ${connection}(first: ${BATCH_SIZE}, after: $cursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes { ${profileFields} }
+      }
+    }
+  }`;
+
+  const profiles: Profile[] = [];
+  let cursor: string | null = null;
+  do {
+    const { viewer } = await github.graphql<{
+      viewer: Record<string, ConnectionPage> | null;
+    }>(query, { cursor });
+    if (viewer === null) {
+      throw new Error(`Unknown user: ${login}`);
+    }
+    const page: ConnectionPage = viewer[connection];
+    profiles.push(...page.nodes.map(toProfile));
+    cursor = page.pageInfo.hasNextPage ? page.pageInfo.endCursor : null;
+  } while (cursor !== null);
+
+  return profiles;
+}
+
+interface ConnectionPage {
+  pageInfo: { hasNextPage: boolean; endCursor: string | null };
+  nodes: GraphQLUser[];
+}
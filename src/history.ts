@@ -0,0 +1,111 @@
+import fs from "fs";
+import path from "path";
+import { cacheForRelations } from "./cache";
+import { currentRun } from "./journal";
+import { settings } from "./settings";
+import { difference } from "./util";
+
+export type SnapshotKind = "followers" | "followings";
+
+// A snapshot is the full list of followers or followings at one point in
+// time. Both lists fetched by the same run share the run's id.
+export interface Snapshot {
+  id: string;
+  timestamp: number;
+  kind: SnapshotKind;
+  logins: string[];
+}
+
+export interface Change {
+  timestamp: number;
+  login: string;
+  change: "gained" | "lost";
+}
+
+// Other accounts analysed with --user get a history of their own.
+export const historyPath = (login = settings.user) =>
+  path.join(
+    path.dirname(cacheForRelations().path),
+    login === undefined
+      ? "history.ndjson"
+      : `history-${login.toLowerCase()}.ndjson`
+  );
+
+// Fetches that find the same logins as the previous snapshot are not
+// recorded; a snapshot describes the network until the next one.
+export function recordSnapshot(
+  kind: SnapshotKind,
+  logins: string[],
+  login = settings.user
+) {
+  const previous = readSnapshots(kind, login).pop();
+  if (previous && sameLogins(previous.logins, logins)) {
+    return;
+  }
+
+  const snapshot: Snapshot = {
+    id: currentRun,
+    timestamp: Date.now(),
+    kind,
+    logins,
+  };
+  fs.mkdirSync(path.dirname(historyPath(login)), { recursive: true });
+  fs.appendFileSync(historyPath(login), JSON.stringify(snapshot) + "\n");
+}
+
+export function readSnapshots(
+  kind: SnapshotKind,
+  login = settings.user
+): Snapshot[] {
+  if (!fs.existsSync(historyPath(login))) {
+    return [];
+  }
+  return fs
+    .readFileSync(historyPath(login), "utf8")
+    .split("\n")
+    .filter((line) => line.length > 0)
+    .map((line) => JSON.parse(line) as Snapshot)
+    .filter((snapshot) => snapshot.kind === kind)
+    .sort((a, b) => a.timestamp - b.timestamp);
+}
+
+// Every gain and loss between consecutive snapshots, dated by the snapshot
+// that first observed it. The first snapshot is the baseline.
+export function changesOf(snapshots: Snapshot[]): Change[] {
+  const changes: Change[] = [];
+  for (let i = 1; i < snapshots.length; i++) {
+    const before = new Set(snapshots[i - 1].logins);
+    const after = new Set(snapshots[i].logins);
+    const { timestamp } = snapshots[i];
+    for (const login of difference(after, before)) {
+      changes.push({ timestamp, login, change: "gained" });
+    }
+    for (const login of difference(before, after)) {
+      changes.push({ timestamp, login, change: "lost" });
+    }
+  }
+  return changes;
+}
+
+// The latest snapshot of the given kind taken at or before `timestamp`.
+export function snapshotAt(
+  kind: SnapshotKind,
+  timestamp: number
+): Snapshot | undefined {
+  return readSnapshots(kind)
+    .filter((snapshot) => snapshot.timestamp <= timestamp)
+    .pop();
+}
+
+// Resolves a snapshot id to the time its run was recorded.
+export function snapshotTime(id: string): number | undefined {
+  const times = [...readSnapshots("followers"), ...readSnapshots("followings")]
+    .filter((snapshot) => snapshot.id === id)
+    .map((snapshot) => snapshot.timestamp);
+  return times.length > 0 ? Math.max(...times) : undefined;
+}
+
+function sameLogins(lhs: string[], rhs: string[]): boolean {
+  const set = new Set(lhs);
+  return lhs.length === rhs.length && rhs.every((login) => set.has(login));
+}
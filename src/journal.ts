@@ -0,0 +1,59 @@
+import fs from "fs";
+import path from "path";
+import { cacheForRelations } from "./cache";
+
+export type JournalAction = "follow" | "unfollow" | "block" | "unblock";
+
+export interface JournalEntry {
+  run: string;
+  timestamp: number;
+  login: string;
+  action: JournalAction;
+  result: "ok" | "failed";
+  error?: string;
+}
+
+export const inverse: Record<JournalAction, JournalAction> = {
+  follow: "unfollow",
+  unfollow: "follow",
+  block: "unblock",
+  unblock: "block",
+};
+
+// Every invocation of the CLI is one run; all actions it performs share this id.
+export const currentRun = Date.now().toString(36);
+
+// The journal lives next to the conf caches so `cache path` points people at it.
+export const journalPath = () =>
+  path.join(path.dirname(cacheForRelations().path), "journal.ndjson");
+
+export function record(entry: Omit<JournalEntry, "run" | "timestamp">) {
+  const line: JournalEntry = {
+    run: currentRun,
+    timestamp: Date.now(),
+    ...entry,
+  };
+  fs.mkdirSync(path.dirname(journalPath()), { recursive: true });
+  fs.appendFileSync(journalPath(), JSON.stringify(line) + "\n");
+}
+
+export function readJournal(): JournalEntry[] {
+  if (!fs.existsSync(journalPath())) {
+    return [];
+  }
+  return fs
+    .readFileSync(journalPath(), "utf8")
+    .split("\n")
+    .filter((line) => line.length > 0)
+    .map((line) => JSON.parse(line) as JournalEntry);
+}
+
+export function groupByRun(
+  entries: JournalEntry[]
+): Map<string, JournalEntry[]> {
+  const runs = new Map<string, JournalEntry[]>();
+  for (const entry of entries) {
+    runs.set(entry.run, [...(runs.get(entry.run) ?? []), entry]);
+  }
+  return runs;
+}
@@ -0,0 +1,60 @@
+import fs from "fs";
+import path from "path";
+import { createClient } from "./github";
+import { configFile } from "./settings";
+import { globToRegExp, readListFile } from "./util";
+
+// allowlist.txt holds accounts that must never be unfollowed or blocked,
+// denylist.txt accounts that must never be followed. Each line is a login, a
+// glob pattern like `*-bot`, or `org:<name>` for every member of an org.
+export type ListName = "allowlist" | "denylist";
+
+export type Matcher = (login: string) => boolean;
+
+export const listPath = (name: ListName) =>
+  path.join(path.dirname(configFile().path), `${name}.txt`);
+
+const loaded = new Map<ListName, Promise<Matcher>>();
+
+export function loadList(name: ListName, auth: string): Promise<Matcher> {
+  let matcher = loaded.get(name);
+  if (matcher === undefined) {
+    matcher = fs.existsSync(listPath(name))
+      ? compile(readListFile(listPath(name)), auth)
+      : Promise.resolve(() => false);
+    loaded.set(name, matcher);
+  }
+  return matcher;
+}
+
+export async function compile(
+  entries: string[],
+  auth: string
+): Promise<Matcher> {
+  const logins = new Set<string>();
+  const patterns: RegExp[] = [];
+  for (const entry of entries) {
+    if (entry.startsWith("org:")) {
+      for (const member of await orgMembers(entry.slice(4), auth)) {
+        logins.add(member.toLowerCase());
+      }
+    } else if (entry.includes("*")) {
+      patterns.push(globToRegExp(entry));
+    } else {
+      logins.add(entry.toLowerCase());
+    }
+  }
+  return (login) =>
+    logins.has(login.toLowerCase()) || patterns.some((re) => re.test(login));
+}
+
+// Members visible to the token: everyone for orgs we belong to, otherwise
+// public members only.
+async function orgMembers(org: string, auth: string): Promise<string[]> {
+  const github = createClient(auth);
+  const members = await github.paginate(github.orgs.listMembers, {
+    org,
+    per_page: 100,
+  });
+  return members.map((member) => (member as { login: string }).login);
+}
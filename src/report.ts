@@ -0,0 +1,174 @@
+import chalk from "chalk";
+import Table from "cli-table";
+import { Args, Options, UsageError } from "./args";
+import { annotationStore, blockedLogins } from "./cache";
+import { getUsers, Relations } from "./github";
+import { loadList } from "./lists";
+import { activeScorer } from "./score";
+import { suspicionReasons } from "./spam";
+
+export const statuses = ["watching", "watcher", "mutual"] as const;
+
+export type Status = typeof statuses[number];
+
+export interface Row {
+  status: Status;
+  login: string;
+  repos: number;
+  followers: number;
+  followings: number;
+  impact: number;
+  // Why the account looks like spam; empty unless it's suspicious.
+  suspicious: string[];
+  // Personal tags from `github-social tag`.
+  tags: string[];
+  // Listed in the allowlist, so bulk actions leave it alone.
+  protected: boolean;
+  url: string;
+}
+
+export const columns: (keyof Row)[] = [
+  "status",
+  "login",
+  "repos",
+  "followers",
+  "followings",
+  "impact",
+  "suspicious",
+  "tags",
+  "protected",
+  "url",
+];
+
+export const statusColor: Record<Status, chalk.Chalk> = {
+  watching: chalk.green,
+  watcher: chalk.magenta,
+  mutual: chalk.cyan,
+};
+
+export interface Network {
+  mutuals: string[];
+  watching: string[];
+  watcher: string[];
+}
+
+// Blocked accounts never show up as watchers, even while a stale cache still
+// lists them as followers.
+export function classify(
+  { followers, followings }: Relations,
+  blocked = blockedLogins()
+): Network {
+  return {
+    mutuals: [...followings].filter((username) => followers.has(username)),
+    watching: [...followings].filter((username) => !followers.has(username)),
+    watcher: [...followers].filter(
+      (username) =>
+        !followings.has(username) && !blocked.has(username.toLowerCase())
+    ),
+  };
+}
+
+export async function buildRows(
+  usernames: string[],
+  status: Status,
+  token: string
+): Promise<Row[]> {
+  const profiles = await getUsers(usernames, token);
+  const scorer = activeScorer();
+  const isProtected = await loadList("allowlist", token);
+  const annotations = annotationStore().store;
+  const rows = await Promise.all(
+    [...profiles.values()].map<Promise<Row>>(async (profile) => ({
+      status,
+      login: profile.login,
+      repos: profile.public_repos,
+      followings: profile.following,
+      followers: profile.followers,
+      impact: scorer.score(profile),
+      suspicious: await suspicionReasons(profile),
+      tags: annotations[profile.login.toLowerCase()]?.tags ?? [],
+      protected: isProtected(profile.login),
+      url: profile.html_url,
+    }))
+  );
+  return rows.sort((a, b) => b.impact - a.impact);
+}
+
+// Rows of the given sections of the network, section by section.
+export async function buildNetworkRows(
+  network: Network,
+  include: Status[],
+  token: string
+): Promise<Row[]> {
+  const sections: Record<Status, string[]> = {
+    watching: network.watching,
+    watcher: network.watcher,
+    mutual: network.mutuals,
+  };
+  const rows: Row[] = [];
+  for (const status of include) {
+    rows.push(...(await buildRows(sections[status], status, token)));
+  }
+  return rows;
+}
+
+export function parseStatuses(values: string[]): Status[] {
+  for (const value of values) {
+    if (!(statuses as readonly string[]).includes(value)) {
+      throw new UsageError(
+        `Unknown status '${value}', expected one of: ${statuses.join(", ")}`
+      );
+    }
+  }
+  return [...new Set(values)] as Status[];
+}
+
+function statusCell(row: Row): string {
+  const status = statusColor[row.status](row.status);
+  return row.protected ? `${status} ${chalk.yellow("protected")}` : status;
+}
+
+// A row's value for a column, with lists joined for display.
+export function cell<R extends Row>(row: R, column: keyof R): string | number {
+  const value: unknown = row[column];
+  if (value === undefined || value === null) return "";
+  return Array.isArray(value) ? value.join(", ") : (value as string | number);
+}
+
+// Commands that add fields to their rows pass their own columns.
+export function renderTable<R extends Row>(
+  rows: R[],
+  cols: (keyof R)[] = columns
+): string {
+  const table = new Table({ head: cols.map(String) });
+  table.push(
+    ...rows.map((row) =>
+      cols.map((column) =>
+        column === "status" ? statusCell(row) : cell(row, column)
+      )
+    )
+  );
+  return table.toString();
+}
+
+export const tagOptions: Options = {
+  tag: {
+    type: "list",
+    placeholder: "tags",
+    description: "Only include accounts tagged with one of these",
+  },
+  "without-tag": {
+    type: "list",
+    placeholder: "tags",
+    description: "Leave out accounts tagged with one of these",
+  },
+};
+
+// Applies --tag and --without-tag from tagOptions.
+export function tagFilter(args: Args): (row: Row) => boolean {
+  const wanted = args.list("tag").map((name) => name.toLowerCase());
+  const unwanted = args.list("without-tag").map((name) => name.toLowerCase());
+  return (row) =>
+    (wanted.length === 0 || row.tags.some((name) => wanted.includes(name))) &&
+    !row.tags.some((name) => unwanted.includes(name));
+}
@@ -0,0 +1,215 @@
+import { schedule } from "./scheduler";
+import { settings } from "./settings";
+
+// Shaped like octokit's RequestError.
+function githubError(
+  status: number,
+  headers: Record<string, string> = {},
+  message = "error"
+) {
+  return Object.assign(new Error(message), {
+    status,
+    response: { headers },
+  });
+}
+
+// Lets pending promise callbacks run; timers stay frozen.
+async function flush() {
+  for (let i = 0; i < 20; i++) {
+    await Promise.resolve();
+  }
+}
+
+function deferred() {
+  let resolve!: () => void;
+  const promise = new Promise<void>((r) => (resolve = r));
+  return { promise, resolve };
+}
+
+// Fails once with `err`, then checks the retry waits exactly `delay` ms.
+async function expectRetryAfter(err: Error, delay: number) {
+  const task = jest.fn().mockRejectedValueOnce(err).mockResolvedValue("ok");
+  const result = schedule(task);
+  await flush();
+  expect(task).toHaveBeenCalledTimes(1);
+
+  jest.advanceTimersByTime(delay - 1);
+  await flush();
+  expect(task).toHaveBeenCalledTimes(1);
+
+  jest.advanceTimersByTime(1);
+  await flush();
+  expect(task).toHaveBeenCalledTimes(2);
+  await expect(result).resolves.toBe("ok");
+}
+
+beforeEach(() => {
+  jest.useFakeTimers("modern");
+  jest.setSystemTime(1_000_000);
+  jest.spyOn(Math, "random").mockReturnValue(0);
+  jest.spyOn(console, "error").mockImplementation(() => undefined);
+  settings.concurrency = 8;
+  settings.retries = 5;
+});
+
+afterEach(() => {
+  jest.useRealTimers();
+  jest.restoreAllMocks();
+});
+
+describe("schedule retries", () => {
+  test("does not retry client errors", async () => {
+    const err = githubError(404);
+    const task = jest.fn().mockRejectedValue(err);
+    await expect(schedule(task)).rejects.toBe(err);
+    expect(task).toHaveBeenCalledTimes(1);
+  });
+
+  test("does not retry a 403 that isn't a rate limit", async () => {
+    const err = githubError(403, {}, "Resource not accessible by integration");
+    const task = jest.fn().mockRejectedValue(err);
+    await expect(schedule(task)).rejects.toBe(err);
+    expect(task).toHaveBeenCalledTimes(1);
+  });
+
+  test("honors Retry-After on a 429", async () => {
+    await expectRetryAfter(githubError(429, { "retry-after": "3" }), 3000);
+  });
+
+  test("honors Retry-After on a secondary rate limit 403", async () => {
+    await expectRetryAfter(
+      githubError(403, { "retry-after": "2" }, "secondary rate limit"),
+      2000
+    );
+  });
+
+  test("waits for x-ratelimit-reset once the quota is used up", async () => {
+    // The system time is 1000s; the quota resets 10s later.
+    await expectRetryAfter(
+      githubError(403, {
+        "x-ratelimit-remaining": "0",
+        "x-ratelimit-reset": "1010",
+      }),
+      10000
+    );
+  });
+
+  test("backs off on a rate limit message without headers", async () => {
+    // Half of the 1s base backoff, with the jitter pinned to 0.
+    await expectRetryAfter(
+      githubError(403, {}, "You have triggered an abuse detection mechanism"),
+      500
+    );
+  });
+
+  test("backs off exponentially on server errors", async () => {
+    const task = jest
+      .fn()
+      .mockRejectedValueOnce(githubError(502))
+      .mockRejectedValueOnce(githubError(503))
+      .mockResolvedValue("ok");
+    const result = schedule(task);
+
+    await flush();
+    jest.advanceTimersByTime(500);
+    await flush();
+    expect(task).toHaveBeenCalledTimes(2);
+
+    jest.advanceTimersByTime(999);
+    await flush();
+    expect(task).toHaveBeenCalledTimes(2);
+    jest.advanceTimersByTime(1);
+    await flush();
+    expect(task).toHaveBeenCalledTimes(3);
+    await expect(result).resolves.toBe("ok");
+  });
+
+  test("gives up after settings.retries", async () => {
+    settings.retries = 2;
+    const err = githubError(500);
+    const task = jest.fn().mockRejectedValue(err);
+    const result = schedule(task).catch((e) => e);
+
+    for (let i = 0; i < 5; i++) {
+      await flush();
+      jest.advanceTimersByTime(60 * 1000);
+    }
+    expect(await result).toBe(err);
+    expect(task).toHaveBeenCalledTimes(3);
+  });
+});
+
+describe("schedule concurrency", () => {
+  test("runs at most settings.concurrency tasks, handing slots over in order", async () => {
+    settings.concurrency = 2;
+    const gates = [deferred(), deferred(), deferred(), deferred()];
+    const started: number[] = [];
+    let running = 0;
+    let peak = 0;
+
+    const results = gates.map((gate, i) =>
+      schedule(async () => {
+        started.push(i);
+        peak = Math.max(peak, ++running);
+        await gate.promise;
+        running--;
+        return i;
+      })
+    );
+
+    await flush();
+    expect(started).toEqual([0, 1]);
+
+    gates[1].resolve();
+    await flush();
+    expect(started).toEqual([0, 1, 2]);
+
+    gates[0].resolve();
+    await flush();
+    expect(started).toEqual([0, 1, 2, 3]);
+
+    gates[2].resolve();
+    gates[3].resolve();
+    expect(await Promise.all(results)).toEqual([0, 1, 2, 3]);
+    expect(peak).toBe(2);
+  });
+
+  test("frees slots once the queue is drained", async () => {
+    settings.concurrency = 2;
+    await Promise.all([schedule(async () => 1), schedule(async () => 2)]);
+
+    const gates = [deferred(), deferred()];
+    let started = 0;
+    const results = gates.map((gate) =>
+      schedule(async () => {
+        started++;
+        await gate.promise;
+      })
+    );
+    await flush();
+    expect(started).toBe(2);
+
+    gates.forEach((gate) => gate.resolve());
+    await Promise.all(results);
+  });
+
+  test("a task waiting to be retried gives up its slot", async () => {
+    settings.concurrency = 1;
+    const failing = jest
+      .fn()
+      .mockRejectedValueOnce(githubError(429, { "retry-after": "5" }))
+      .mockResolvedValue("retried");
+    const other = jest.fn().mockResolvedValue("other");
+
+    const first = schedule(failing);
+    await flush();
+    const second = schedule(other);
+    await flush();
+    expect(other).toHaveBeenCalledTimes(1);
+    await expect(second).resolves.toBe("other");
+
+    jest.advanceTimersByTime(5000);
+    await flush();
+    await expect(first).resolves.toBe("retried");
+  });
+});
@@ -0,0 +1,78 @@
+import { settings } from "./settings";
+
+const MAX_BACKOFF = 60 * 1000;
+
+let active = 0;
+const queue: (() => void)[] = [];
+
+// Runs `task` once fewer than `settings.concurrency` tasks are in flight, and
+// retries it when GitHub answers with a rate limit or server error. A task
+// gives up its slot while it waits to be retried.
+export async function schedule<T>(task: () => Promise<T>): Promise<T> {
+  for (let attempt = 0; ; attempt++) {
+    try {
+      return await limit(task);
+    } catch (err) {
+      const delay = retryDelay(err, attempt);
+      if (delay === undefined || attempt >= settings.retries) {
+        throw err;
+      }
+      console.error(
+        `GitHub responded ${err.status}; retrying in ${Math.ceil(
+          delay / 1000
+        )}s (${attempt + 1}/${settings.retries})`
+      );
+      await sleep(delay);
+    }
+  }
+}
+
+async function limit<T>(task: () => Promise<T>): Promise<T> {
+  if (active < settings.concurrency) {
+    active++;
+  } else {
+    // The finishing task hands its slot over without releasing it.
+    await new Promise<void>((resolve) => queue.push(resolve));
+  }
+  try {
+    return await task();
+  } finally {
+    const next = queue.shift();
+    if (next) {
+      next();
+    } else {
+      active--;
+    }
+  }
+}
+
+// How long to wait before retrying, or `undefined` if the error is final.
+function retryDelay(err: any, attempt: number): number | undefined {
+  const status: number | undefined = err?.status;
+  const headers: Record<string, string | undefined> =
+    err?.response?.headers ?? {};
+
+  const rateLimited =
+    status === 429 ||
+    (status === 403 &&
+      (headers["retry-after"] !== undefined ||
+        headers["x-ratelimit-remaining"] === "0" ||
+        /rate limit|abuse/i.test(err?.message ?? "")));
+  if (!rateLimited && !(status !== undefined && status >= 500)) {
+    return undefined;
+  }
+
+  if (headers["retry-after"] !== undefined) {
+    return Number(headers["retry-after"]) * 1000;
+  }
+  const reset = headers["x-ratelimit-reset"];
+  if (headers["x-ratelimit-remaining"] === "0" && reset !== undefined) {
+    return Math.max(0, Number(reset) * 1000 - Date.now());
+  }
+  const backoff = Math.min(MAX_BACKOFF, 1000 * 2 ** attempt);
+  return backoff / 2 + Math.random() * (backoff / 2);
+}
+
+function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
@@ -0,0 +1,105 @@
+import { UsageError } from "./args";
+import type { Profile } from "./github";
+import { configFile, settings } from "./settings";
+
+export interface Scorer {
+  description: string;
+  score(profile: Profile): number;
+}
+
+// Inputs a weighted formula from config.json can combine.
+const features: Record<string, (profile: Profile) => number> = {
+  followers: (p) => p.followers,
+  following: (p) => p.following,
+  repos: (p) => p.public_repos,
+  logFollowers: (p) => Math.log10(p.followers + 1),
+  logFollowing: (p) => Math.log10(p.following + 1),
+  logRepos: (p) => Math.log10(p.public_repos + 1),
+  ratio: (p) => p.followers / (p.following + 1),
+  ageYears: (p) => accountAgeYears(p),
+};
+
+export type Weights = Record<string, number>;
+
+export const builtinScorers: Record<string, Scorer> = {
+  impact: {
+    description: "log10(repos) + followers / followings (the original formula)",
+    score: (p) =>
+      calculateImpactFactor(p.followers, p.following, p.public_repos),
+  },
+  "follower-ratio": {
+    description: "followers / (followings + 1)",
+    score: (p) => p.followers / (p.following + 1),
+  },
+  "log-followers": {
+    description: "log10(followers + 1)",
+    score: (p) => Math.log10(p.followers + 1),
+  },
+  activity: {
+    description:
+      "log10(followers + 1) + 2 * log10(repos per year of account age + 1)",
+    score: (p) =>
+      Math.log10(p.followers + 1) +
+      2 * Math.log10(p.public_repos / Math.max(accountAgeYears(p), 1) + 1),
+  },
+};
+
+export function calculateImpactFactor(
+  followerCount: number,
+  followingsCount: number,
+  repoCount: number
+): number {
+  return (
+    Math.log10(repoCount + 0.00001) +
+    (followerCount + 0.00001) / (followingsCount + 0.00001)
+  );
+}
+
+// Built-in scorers plus the weighted formulas under `scorers` in config.json,
+// e.g. `{ "scorers": { "mine": { "logFollowers": 1, "logRepos": 2 } } }`.
+export function scorers(): Record<string, Scorer> {
+  const custom = configFile().get("scorers") ?? {};
+  const result = { ...builtinScorers };
+  for (const [name, weights] of Object.entries(custom)) {
+    for (const feature of Object.keys(weights)) {
+      if (!(feature in features)) {
+        throw new Error(
+          `Unknown input '${feature}' in scorer '${name}', expected one of: ${Object.keys(
+            features
+          ).join(", ")}`
+        );
+      }
+    }
+    result[name] = {
+      description: Object.entries(weights)
+        .map(([feature, weight]) => `${weight} * ${feature}`)
+        .join(" + "),
+      score: (p) =>
+        Object.entries(weights).reduce(
+          (sum, [feature, weight]) => sum + weight * features[feature](p),
+          0
+        ),
+    };
+  }
+  return result;
+}
+
+// The scorer picked with --score, which drives the impact column.
+export function activeScorer(): Scorer {
+  const all = scorers();
+  const scorer = all[settings.score];
+  if (scorer === undefined) {
+    throw new UsageError(
+      `Unknown scorer '${settings.score}', expected one of: ${Object.keys(
+        all
+      ).join(", ")}`
+    );
+  }
+  return scorer;
+}
+
+function accountAgeYears(profile: Profile): number {
+  return (
+    (Date.now() - Date.parse(profile.created_at)) / (365 * 24 * 60 * 60 * 1000)
+  );
+}
@@ -0,0 +1,204 @@
+import { Args, Options, UsageError } from "./args";
+import { getConfig } from "./cache";
+import type { Weights } from "./score";
+import { parseDuration } from "./util";
+
+export interface Settings {
+  // Whose network to analyse; the authenticated user when undefined.
+  user?: string;
+  // How long cached followers and followings are considered fresh, in milliseconds.
+  relationsTtl: number;
+  // How long a cached user profile is considered fresh, in milliseconds.
+  userTtl: number;
+  // Ignore cached data and refetch everything from GitHub.
+  refresh: boolean;
+  // Max number of requests to GitHub in flight at once.
+  concurrency: number;
+  // How often a rate-limited or failed request is retried.
+  retries: number;
+  // Fetch profiles one by one over REST instead of batching them over GraphQL.
+  rest: boolean;
+  // Fetch followers and followings together with their profiles over GraphQL.
+  onePass: boolean;
+  // Name of the scorer that drives the impact column and sort order.
+  score: string;
+  // Spend a request per profile to tell generated identicons from uploads.
+  checkAvatars: boolean;
+}
+
+// Keys of config.json, which lives next to the caches.
+export type ConfigFile = {
+  // Durations like "10m", or a number of seconds.
+  relationsTtl?: string | number;
+  userTtl?: string | number;
+  concurrency?: number;
+  retries?: number;
+  onePass?: boolean;
+  score?: string;
+  scorers?: Record<string, Weights>;
+};
+
+export const DEFAULT_RELATIONS_TTL = 60 * 60 * 1000;
+export const DEFAULT_USER_TTL = 7 * 24 * 60 * 60 * 1000;
+
+export const settings: Settings = {
+  relationsTtl: DEFAULT_RELATIONS_TTL,
+  userTtl: DEFAULT_USER_TTL,
+  refresh: false,
+  concurrency: 8,
+  retries: 5,
+  rest: false,
+  onePass: false,
+  score: "impact",
+  checkAvatars: false,
+};
+
+export const configFile = () => getConfig<ConfigFile>("config");
+
+export const settingsOptions: Options = {
+  user: {
+    type: "string",
+    alias: "u",
+    placeholder: "login",
+    description: "Analyse another user's network instead of your own",
+  },
+  "relations-ttl": {
+    type: "string",
+    placeholder: "duration",
+    description:
+      "Max age of cached followers and followings (default: 1h, env: GITHUB_SOCIAL_RELATIONS_TTL)",
+  },
+  "user-ttl": {
+    type: "string",
+    placeholder: "duration",
+    description:
+      "Max age of cached profiles, e.g. 12h or 7d (default: 7d, env: GITHUB_SOCIAL_USER_TTL)",
+  },
+  refresh: {
+    type: "boolean",
+    alias: "r",
+    description: "Ignore cached data and refetch it",
+  },
+  "no-cache": {
+    type: "boolean",
+    description: "Same as --refresh",
+  },
+  concurrency: {
+    type: "number",
+    placeholder: "n",
+    description:
+      "Max parallel requests to GitHub (default: 8, env: GITHUB_SOCIAL_CONCURRENCY)",
+  },
+  retries: {
+    type: "number",
+    placeholder: "n",
+    description:
+      "Retries for rate-limited or failed requests (default: 5, env: GITHUB_SOCIAL_RETRIES)",
+  },
+  rest: {
+    type: "boolean",
+    description: "Fetch profiles one by one over REST instead of GraphQL",
+  },
+  "one-pass": {
+    type: "boolean",
+    description:
+      "Fetch followers and followings with their profiles in one GraphQL pass",
+  },
+  score: {
+    type: "string",
+    placeholder: "name",
+    description:
+      "Scorer for the impact column: impact, follower-ratio, log-followers, activity or one from config.json",
+  },
+  "check-avatars": {
+    type: "boolean",
+    description:
+      "Count default avatars towards spam detection (one request per user)",
+  },
+};
+
+// Flags take precedence over env vars, which take precedence over config.json.
+export function configure(args: Args) {
+  const config = configFile();
+
+  settings.user = args.string("user");
+
+  const relationsTtl =
+    args.string("relations-ttl") ??
+    process.env["GITHUB_SOCIAL_RELATIONS_TTL"] ??
+    config.get("relationsTtl");
+  if (relationsTtl !== undefined) {
+    settings.relationsTtl = durationSetting(
+      "relations-ttl",
+      "relationsTtl",
+      relationsTtl
+    );
+  }
+
+  const userTtl =
+    args.string("user-ttl") ??
+    process.env["GITHUB_SOCIAL_USER_TTL"] ??
+    config.get("userTtl");
+  if (userTtl !== undefined) {
+    settings.userTtl = durationSetting("user-ttl", "userTtl", userTtl);
+  }
+
+  settings.refresh = args.boolean("refresh") || args.boolean("no-cache");
+  settings.rest = args.boolean("rest");
+  settings.checkAvatars = args.boolean("check-avatars");
+  settings.score = args.string("score") ?? config.get("score") ?? "impact";
+  settings.onePass = args.has("one-pass")
+    ? args.boolean("one-pass")
+    : config.get("onePass") ?? false;
+
+  const concurrency =
+    args.number("concurrency") ??
+    envNumber("GITHUB_SOCIAL_CONCURRENCY") ??
+    config.get("concurrency");
+  if (concurrency !== undefined) {
+    if (!Number.isInteger(concurrency) || concurrency < 1) {
+      throw new UsageError(`Invalid concurrency: ${concurrency}`);
+    }
+    settings.concurrency = concurrency;
+  }
+
+  const retries =
+    args.number("retries") ??
+    envNumber("GITHUB_SOCIAL_RETRIES") ??
+    config.get("retries");
+  if (retries !== undefined) {
+    if (!Number.isInteger(retries) || retries < 0) {
+      throw new UsageError(`Invalid retries: ${retries}`);
+    }
+    settings.retries = retries;
+  }
+}
+
+function envNumber(name: string): number | undefined {
+  const value = process.env[name];
+  return value === undefined ? undefined : Number(value);
+}
+
+// Flags and env vars are always strings; config.json may also hold a number of
+// seconds, or anything else by mistake.
+function durationSetting(
+  name: string,
+  configKey: string,
+  value: unknown
+): number {
+  if (typeof value === "number" && Number.isFinite(value) && value >= 0) {
+    return value * 1000;
+  }
+  if (typeof value !== "string") {
+    throw new UsageError(
+      `Invalid ${configKey} in config.json: expected a duration like "10m" or a number of seconds, got ${JSON.stringify(
+        value
+      )}`
+    );
+  }
+  const ms = parseDuration(value);
+  if (ms === undefined) {
+    throw new UsageError(`Invalid duration for ${name}: '${value}'`);
+  }
+  return ms;
+}
@@ -0,0 +1,95 @@
+import https from "https";
+import type { Profile } from "./github";
+import { schedule } from "./scheduler";
+import { settings } from "./settings";
+
+// A profile is suspicious once the weights of its signals add up to this.
+export const SUSPICION_THRESHOLD = 3;
+
+const NEW_ACCOUNT_DAYS = 30;
+
+const AVATAR_TIMEOUT = 10 * 1000;
+
+interface Signal {
+  weight: number;
+  test(profile: Profile): string | undefined;
+}
+
+const signals: Signal[] = [
+  {
+    weight: 3,
+    test: (p) =>
+      p.following >= 2000 ? `follows ${p.following} accounts` : undefined,
+  },
+  {
+    weight: 2,
+    test: (p) =>
+      p.following >= 100 && p.following >= 10 * (p.followers + 1)
+        ? `follows ${Math.round(
+            p.following / (p.followers + 1)
+          )}x more accounts than follow it`
+        : undefined,
+  },
+  {
+    weight: 1,
+    test: (p) => (p.public_repos === 0 ? "no public repos" : undefined),
+  },
+  {
+    weight: 1,
+    test: (p) => {
+      const days = (Date.now() - Date.parse(p.created_at)) / 86400000;
+      return days < NEW_ACCOUNT_DAYS
+        ? `created ${Math.floor(days)} days ago`
+        : undefined;
+    },
+  },
+  {
+    weight: 1,
+    test: (p) => (!p.bio ? "no bio" : undefined),
+  },
+];
+
+// Reasons a profile looks like a follow farm or an empty account, or an
+// empty list when it doesn't add up to SUSPICION_THRESHOLD.
+export async function suspicionReasons(profile: Profile): Promise<string[]> {
+  let weight = 0;
+  const reasons: string[] = [];
+  for (const signal of signals) {
+    const reason = signal.test(profile);
+    if (reason !== undefined) {
+      weight += signal.weight;
+      reasons.push(reason);
+    }
+  }
+  if (settings.checkAvatars && (await hasDefaultAvatar(profile))) {
+    weight += 1;
+    reasons.push("default avatar");
+  }
+  return weight >= SUSPICION_THRESHOLD ? reasons : [];
+}
+
+// Profile data doesn't say whether an avatar was uploaded. Generated
+// identicons are always served as PNG while uploaded photos are mostly
+// JPEG, which is a good enough hint at the cost of one request per user.
+// Probes share the API's concurrency limit, and a stalled one counts as
+// uploaded.
+function hasDefaultAvatar(profile: Profile): Promise<boolean> {
+  const url = new URL(profile.avatar_url);
+  url.searchParams.set("s", "1");
+  return schedule(
+    () =>
+      new Promise<boolean>((resolve) => {
+        const req = https
+          .request(url, { method: "HEAD" }, (res) => {
+            resolve(res.headers["content-type"] === "image/png");
+            res.resume();
+          })
+          .on("error", () => resolve(false));
+        req.setTimeout(AVATAR_TIMEOUT, () => {
+          req.destroy();
+          resolve(false);
+        });
+        req.end();
+      })
+  );
+}
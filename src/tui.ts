@@ -0,0 +1,411 @@
+import chalk from "chalk";
+import readline from "readline";
+import { applyToLogins } from "./bulk";
+import { addTags } from "./cache";
+import { compileSort } from "./filter";
+import { JournalAction } from "./journal";
+import { columns, Row, statusColor } from "./report";
+
+// A full-screen list of report rows driven by the keyboard. Actions go through
+// the same applyToLogins as the bulk commands, so they are journaled, guarded
+// by the allow/deny lists and update the relations cache; rows are then
+// reloaded from it.
+
+const sortable: (keyof Row)[] = [
+  "impact",
+  "followers",
+  "followings",
+  "repos",
+  "login",
+  "status",
+];
+
+const help =
+  "j/k move  space select  a all  / search  s sort  r reverse  " +
+  "f follow  u unfollow  b block  t tag  o url  q quit";
+
+type Mode =
+  | { kind: "browse" }
+  | {
+      kind: "input";
+      label: string;
+      value: string;
+      submit(value: string): void;
+      cancel(): void;
+    }
+  | { kind: "confirm"; question: string; accept(): Promise<void> }
+  | { kind: "busy" }
+  | { kind: "paused" };
+
+export class Triage {
+  private rows: Row[] = [];
+  private view: Row[] = [];
+  private cursor = 0;
+  private offset = 0;
+  private selected = new Set<string>();
+  private query = "";
+  private sortColumn = 0;
+  private descending = true;
+  private mode: Mode = { kind: "browse" };
+  private message = "";
+  // Profile URLs asked for with `o`, printed again once the screen is gone.
+  private urls: string[] = [];
+  private done?: () => void;
+
+  constructor(
+    private load: () => Promise<Row[]>,
+    private token: string
+  ) {}
+
+  async run(): Promise<void> {
+    if (!process.stdin.isTTY || !process.stdout.isTTY) {
+      throw new Error("triage needs an interactive terminal");
+    }
+    this.rows = await this.load();
+    this.refreshView();
+
+    const onKey = (
+      input: string | undefined,
+      key: readline.Key | undefined
+    ) =>
+      this.handle(input, key ?? {}).catch((err) => {
+        this.mode = { kind: "browse" };
+        this.message = `error: ${err.message}`;
+        this.render();
+      });
+    const onResize = () => this.render();
+
+    readline.emitKeypressEvents(process.stdin);
+    process.stdin.setRawMode(true);
+    process.stdin.on("keypress", onKey);
+    process.stdout.on("resize", onResize);
+    // Alternate screen, hidden cursor.
+    process.stdout.write("\x1b[?1049h\x1b[?25l");
+    this.render();
+
+    await new Promise<void>((resolve) => (this.done = resolve));
+
+    process.stdout.write("\x1b[?25h\x1b[?1049l");
+    process.stdout.off("resize", onResize);
+    process.stdin.off("keypress", onKey);
+    process.stdin.setRawMode(false);
+    process.stdin.pause();
+    for (const url of this.urls) {
+      console.log(url);
+    }
+  }
+
+  private async handle(input: string | undefined, key: readline.Key) {
+    if (key.ctrl && key.name === "c") {
+      this.done?.();
+      return;
+    }
+    switch (this.mode.kind) {
+      case "busy":
+        return;
+      case "paused":
+        this.mode = { kind: "browse" };
+        break;
+      case "input":
+        this.edit(this.mode, input, key);
+        break;
+      case "confirm": {
+        const { accept } = this.mode;
+        this.mode = { kind: "browse" };
+        if (input === "y" || input === "Y") {
+          await accept();
+          return;
+        }
+        this.message = "cancelled";
+        break;
+      }
+      case "browse":
+        this.browse(input, key);
+        break;
+    }
+    this.render();
+  }
+
+  private edit(
+    mode: Extract<Mode, { kind: "input" }>,
+    input: string | undefined,
+    key: readline.Key
+  ) {
+    if (key.name === "return") {
+      this.mode = { kind: "browse" };
+      mode.submit(mode.value);
+    } else if (key.name === "escape") {
+      this.mode = { kind: "browse" };
+      mode.cancel();
+    } else if (key.name === "backspace") {
+      mode.value = mode.value.slice(0, -1);
+    } else if (input !== undefined && !key.ctrl && input >= " ") {
+      mode.value += input;
+    }
+    if (this.mode === mode && mode.label === "/") {
+      this.query = mode.value;
+      this.refreshView();
+    }
+  }
+
+  private browse(input: string | undefined, key: readline.Key) {
+    const page = this.pageSize();
+    this.message = "";
+    switch (key.name === "space" ? "space" : input ?? key.name) {
+      case "q":
+        this.done?.();
+        return;
+      case "j":
+      case "down":
+        this.moveTo(this.cursor + 1);
+        break;
+      case "k":
+      case "up":
+        this.moveTo(this.cursor - 1);
+        break;
+      case "pagedown":
+        this.moveTo(this.cursor + page);
+        break;
+      case "pageup":
+        this.moveTo(this.cursor - page);
+        break;
+      case "g":
+      case "home":
+        this.moveTo(0);
+        break;
+      case "G":
+      case "end":
+        this.moveTo(this.view.length - 1);
+        break;
+      case "space": {
+        const row = this.view[this.cursor];
+        if (row === undefined) break;
+        if (!this.selected.delete(row.login)) {
+          this.selected.add(row.login);
+        }
+        this.moveTo(this.cursor + 1);
+        break;
+      }
+      case "a":
+        // Select every row in view, or clear the selection if all are.
+        if (this.view.every((row) => this.selected.has(row.login))) {
+          this.selected.clear();
+        } else {
+          this.view.forEach((row) => this.selected.add(row.login));
+        }
+        break;
+      case "/": {
+        const previous = this.query;
+        this.mode = {
+          kind: "input",
+          label: "/",
+          value: this.query,
+          submit: () => undefined,
+          cancel: () => {
+            this.query = previous;
+            this.refreshView();
+          },
+        };
+        break;
+      }
+      case "s":
+        this.sortColumn = (this.sortColumn + 1) % sortable.length;
+        this.descending = sortable[this.sortColumn] !== "login";
+        this.refreshView();
+        break;
+      case "r":
+        this.descending = !this.descending;
+        this.refreshView();
+        break;
+      case "f":
+        this.ask("follow");
+        break;
+      case "u":
+        this.ask("unfollow");
+        break;
+      case "b":
+        this.ask("block");
+        break;
+      case "t": {
+        const logins = this.targets();
+        if (logins.length === 0) break;
+        this.mode = {
+          kind: "input",
+          label: `tag ${describe(logins)}: `,
+          value: "",
+          submit: (value) => this.tag(logins, value.split(/[\s,]+/)),
+          cancel: () => undefined,
+        };
+        break;
+      }
+      case "o": {
+        const urls = this.targetRows().map((row) => row.url);
+        this.urls.push(...urls);
+        this.message =
+          urls.length === 1 ? urls[0] : `${urls.length} URLs printed on exit`;
+        break;
+      }
+    }
+  }
+
+  private ask(action: JournalAction) {
+    const logins = this.targets();
+    if (logins.length === 0) return;
+    this.mode = {
+      kind: "confirm",
+      question: `${action} ${describe(logins)}? [y/N]`,
+      accept: () => this.apply(action, logins),
+    };
+  }
+
+  // Runs the action in plain output so its per-login results stay readable,
+  // then waits for a key before going back to the list.
+  private async apply(action: JournalAction, logins: string[]) {
+    this.mode = { kind: "busy" };
+    process.stdout.write("\x1b[H\x1b[2J");
+    const failed = await applyToLogins(logins, action, this.token);
+    this.selected.clear();
+    this.rows = await this.load();
+    this.refreshView();
+    console.log();
+    console.log(
+      `${failed ? chalk.red("some actions failed") : "done"}; press any key`
+    );
+    this.mode = { kind: "paused" };
+  }
+
+  private tag(logins: string[], tags: string[]) {
+    for (const login of logins) {
+      const all = addTags(login, tags);
+      this.rows
+        .filter((row) => row.login === login)
+        .forEach((row) => (row.tags = all));
+    }
+    this.message = `tagged ${describe(logins)}`;
+  }
+
+  // The selected rows, or the one under the cursor when nothing is selected.
+  private targetRows(): Row[] {
+    if (this.selected.size > 0) {
+      return this.rows.filter((row) => this.selected.has(row.login));
+    }
+    const row = this.view[this.cursor];
+    return row === undefined ? [] : [row];
+  }
+
+  private targets(): string[] {
+    return this.targetRows().map((row) => row.login);
+  }
+
+  private refreshView() {
+    const query = this.query.toLowerCase();
+    const sort = compileSort<Row>(
+      `${sortable[this.sortColumn]}:${this.descending ? "desc" : "asc"}`,
+      columns
+    );
+    this.view = this.rows
+      .filter(
+        (row) =>
+          row.login.toLowerCase().includes(query) ||
+          row.tags.some((name) => name.includes(query))
+      )
+      .sort(sort);
+    this.moveTo(this.cursor);
+  }
+
+  private moveTo(index: number) {
+    this.cursor = Math.max(0, Math.min(index, this.view.length - 1));
+  }
+
+  private pageSize(): number {
+    return Math.max((process.stdout.rows ?? 24) - 4, 1);
+  }
+
+  private render() {
+    if (this.mode.kind === "busy" || this.mode.kind === "paused") return;
+    const width = process.stdout.columns ?? 80;
+    const page = this.pageSize();
+    if (this.cursor < this.offset) this.offset = this.cursor;
+    if (this.cursor >= this.offset + page) this.offset = this.cursor - page + 1;
+
+    const title = [
+      `${this.view.length}/${this.rows.length} rows`,
+      `${this.selected.size} selected`,
+      `sort ${sortable[this.sortColumn]} ${this.descending ? "desc" : "asc"}`,
+      ...(this.query ? [`search "${this.query}"`] : []),
+    ].join(", ");
+    const body = this.view
+      .slice(this.offset, this.offset + page)
+      .map((row, i) => this.line(row, this.offset + i === this.cursor, width));
+    while (body.length < page) body.push("");
+
+    const footer =
+      this.mode.kind === "input"
+        ? fit(`${this.mode.label}${this.mode.value}_`, width)
+        : this.mode.kind === "confirm"
+        ? chalk.yellow(fit(this.mode.question, width))
+        : this.message
+        ? fit(this.message, width)
+        : chalk.gray(fit(help, width));
+
+    process.stdout.write(
+      "\x1b[H\x1b[2J" +
+        [
+          chalk.bold(fit(title, width)),
+          chalk.gray(fit(header(), width)),
+          ...body,
+          footer,
+        ].join("\n")
+    );
+  }
+
+  private line(row: Row, current: boolean, width: number): string {
+    const mark = this.selected.has(row.login) ? "*" : " ";
+    const flags =
+      (row.suspicious.length > 0 ? "!" : " ") + (row.protected ? "P" : " ");
+    const plain = fit(
+      [
+        mark + flags,
+        row.status.padEnd(8),
+        fit(row.login, 24).padEnd(24),
+        String(row.repos).padStart(6),
+        String(row.followers).padStart(9),
+        String(row.followings).padStart(10),
+        row.impact.toFixed(2).padStart(7),
+        row.tags.join(", "),
+      ].join(" "),
+      width
+    );
+    if (current) return chalk.inverse(plain.padEnd(width));
+    // The status always starts right after the three mark characters.
+    return (
+      plain.slice(0, 4) +
+      statusColor[row.status](plain.slice(4, 12)) +
+      plain.slice(12)
+    );
+  }
+}
+
+function header(): string {
+  return [
+    "   ",
+    "status".padEnd(8),
+    "login".padEnd(24),
+    "repos".padStart(6),
+    "followers".padStart(9),
+    "followings".padStart(10),
+    "impact".padStart(7),
+    "tags",
+  ].join(" ");
+}
+
+// Cuts plain text to the terminal width.
+function fit(text: string, width: number): string {
+  return text.length > width
+    ? text.slice(0, Math.max(width - 1, 0)) + "…"
+    : text;
+}
+
+function describe(logins: string[]): string {
+  return logins.length === 1 ? logins[0] : `${logins.length} accounts`;
+}
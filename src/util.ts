@@ -0,0 +1,92 @@
+import fs from "fs";
+
+export function difference<T>(lhs: Set<T>, rhs: Set<T>) {
+  return new Set([...lhs].filter((x) => !rhs.has(x)));
+}
+
+export function intersect<T>(lhs: Set<T>, rhs: Set<T>) {
+  return new Set([...lhs].filter((x) => rhs.has(x)));
+}
+
+export function chunk<T>(items: T[], size: number): T[][] {
+  const chunks: T[][] = [];
+  for (let i = 0; i < items.length; i += size) {
+    chunks.push(items.slice(i, i + size));
+  }
+  return chunks;
+}
+
+export function globToRegExp(pattern: string): RegExp {
+  const source = pattern
+    .split("*")
+    .map((part) => part.replace(/[.+?^${}()|[\]\\]/g, "\\$&"))
+    .join(".*");
+  return new RegExp(`^${source}$`, "i");
+}
+
+export function readListFile(path: string): string[] {
+  return fs
+    .readFileSync(path, "utf8")
+    .split(/\r?\n/)
+    .map((line) => line.replace(/#.*/, "").trim())
+    .filter((line) => line.length > 0);
+}
+
+const durationUnits: Record<string, number> = {
+  ms: 1,
+  s: 1000,
+  m: 60 * 1000,
+  h: 60 * 60 * 1000,
+  d: 24 * 60 * 60 * 1000,
+  w: 7 * 24 * 60 * 60 * 1000,
+};
+
+// Parses durations like "90s", "12h" or "7d" into milliseconds. A bare
+// number is taken as seconds.
+export function parseDuration(value: string): number | undefined {
+  const match = /^(\d+(?:\.\d+)?)\s*(ms|s|m|h|d|w)?$/.exec(value.trim());
+  if (!match) {
+    return undefined;
+  }
+  return Number(match[1]) * durationUnits[match[2] ?? "s"];
+}
+
+export function formatDuration(ms: number): string {
+  const units: [string, number][] = [
+    ["d", durationUnits.d],
+    ["h", durationUnits.h],
+    ["m", durationUnits.m],
+  ];
+  for (const [unit, size] of units) {
+    if (ms >= size) {
+      return `${Math.floor(ms / size)}${unit}`;
+    }
+  }
+  return `${Math.floor(ms / 1000)}s`;
+}
+
+// Local calendar date as YYYY-MM-DD.
+export function formatDate(timestamp: number): string {
+  const date = new Date(timestamp);
+  return [
+    date.getFullYear(),
+    String(date.getMonth() + 1).padStart(2, "0"),
+    String(date.getDate()).padStart(2, "0"),
+  ].join("-");
+}
+
+// The Monday starting the week of the given timestamp, as YYYY-MM-DD.
+export function formatWeek(timestamp: number): string {
+  const date = new Date(timestamp);
+  date.setDate(date.getDate() - ((date.getDay() + 6) % 7));
+  return formatDate(date.getTime());
+}
+
+// Accepts YYYY-MM-DD (local midnight) or any full timestamp Date understands.
+export function parseDate(value: string): number | undefined {
+  const day = /^(\d{4})-(\d{2})-(\d{2})$/.exec(value);
+  const timestamp = day
+    ? new Date(Number(day[1]), Number(day[2]) - 1, Number(day[3])).getTime()
+    : Date.parse(value);
+  return Number.isNaN(timestamp) ? undefined : timestamp;
+}